@@ -0,0 +1,55 @@
+//! Time per `RouteCache` delta for growing routes, under both penalty models. With `TimeWarp`
+//! the time stays flat; with `Legacy` it grows with the route once moves make it infeasible.
+//!
+//! Run with `cargo bench --bench route_cache`.
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use memetic_ga::genetic::route_cache::RouteCache;
+use memetic_ga::structs::config::{Config, PenaltyModel};
+use memetic_ga::structs::io::Info;
+use memetic_ga::util::generate::{generate_instance, GeneratorOptions};
+
+const REPEATS: usize = 20;
+
+fn main() {
+    let info = Info::try_from(generate_instance(&GeneratorOptions {
+        patients: 400, time_window_tightness: 0.3, seed: Some(1), ..GeneratorOptions::default()
+    }).unwrap()).unwrap();
+    let mut config = Config::new("config/config.yaml");
+
+    println!("{:<10} {:>6} {:>14} {:>14}", "model", "length", "insert (ns)", "swap (ns)");
+    for model in [PenaltyModel::TimeWarp, PenaltyModel::Legacy] {
+        config.penalty_model = model;
+        for length in [10, 40, 160, 400] {
+            let route: Vec<i32> = (0..length).collect();
+            let cache = RouteCache::new(&route, &info, &config);
+            let (insert, swap) = (time_inserts(&cache, &route, &info, &config), time_swaps(&cache, &route, &info, &config));
+            println!("{:<10} {:>6} {:>14.1} {:>14.1}", format!("{:?}", model), length, insert, swap);
+        }
+    }
+}
+
+/// Nanoseconds per `insert_delta` of a patient that is not in the route
+fn time_inserts(cache: &RouteCache, route: &[i32], info: &Info, config: &Config) -> f64 {
+    let patient = info.patients.len() as i32 - 1;
+    let start = Instant::now();
+    for _ in 0..REPEATS {
+        for position in 0..=route.len() {
+            black_box(cache.insert_delta(route, position, &[patient], info, config));
+        }
+    }
+    start.elapsed().as_nanos() as f64 / (REPEATS * (route.len() + 1)) as f64
+}
+
+/// Nanoseconds per `swap_delta` of two positions
+fn time_swaps(cache: &RouteCache, route: &[i32], info: &Info, config: &Config) -> f64 {
+    let start = Instant::now();
+    for _ in 0..REPEATS {
+        for i in 0..route.len() {
+            black_box(cache.swap_delta(route, i, route.len() - 1 - i, info, config));
+        }
+    }
+    start.elapsed().as_nanos() as f64 / (REPEATS * route.len()) as f64
+}
@@ -0,0 +1,254 @@
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+use clap::{Args, Parser, Subcommand};
+
+use crate::genetic::evaluate::fitness_nurse;
+use crate::genetic::intra_route::polish;
+use crate::structs::config::Config;
+use crate::structs::io::{read_instance, Info};
+use crate::structs::nurse::Nurse;
+use crate::util::generate::{generate_instance, write_instance, GeneratorOptions, Layout};
+use crate::util::plot::{plot_best_individual, plot_individual_file};
+use crate::util::print::print_best_solution;
+use crate::util::save_individual::{new_run_id, read_individual, save_individual};
+use crate::util::validate::validate_solution;
+use crate::Solver;
+
+#[derive(Parser, Debug)]
+#[command(about = "Memetic genetic algorithm for the home care nurse routing problem")]
+pub struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Run the genetic algorithm
+    Solve(RunArgs),
+    /// Plot a solution. Uses the best saved individual when no solution is given
+    Plot {
+        #[command(flatten)]
+        run: RunArgs,
+        /// Solution file to plot
+        #[arg(long)]
+        solution: Option<PathBuf>,
+        /// Folder to write the plot to
+        #[arg(long, default_value = "plots")]
+        plot_dir: String,
+    },
+    /// Print the fitness and the route schedule of a saved solution
+    Evaluate {
+        #[command(flatten)]
+        run: RunArgs,
+        /// Solution file to evaluate
+        solution: PathBuf,
+    },
+    /// Check that a saved solution visits every patient once and breaks no constraints
+    Validate {
+        #[command(flatten)]
+        run: RunArgs,
+        /// Solution file to validate
+        solution: PathBuf,
+    },
+    /// Generate a synthetic instance with coordinates, time windows and demands
+    Generate(GenerateArgs),
+}
+
+#[derive(Args, Debug)]
+struct GenerateArgs {
+    /// File to write the instance JSON to. Its file stem becomes the instance name
+    output: PathBuf,
+    #[arg(short, long, default_value_t = 100)]
+    patients: usize,
+    #[arg(long, value_enum, default_value_t = Layout::Random)]
+    layout: Layout,
+    /// Number of cluster centres for the clustered and mixed layouts
+    #[arg(long, default_value_t = 8)]
+    clusters: usize,
+    /// Size of the square the patients are placed in
+    #[arg(long, default_value_t = 100.)]
+    grid_size: f64,
+    /// Depot return time
+    #[arg(long, default_value_t = 1000)]
+    horizon: u32,
+    /// From 0 (whole day) to 1 (no slack beyond the care time)
+    #[arg(short, long, default_value_t = 0.5)]
+    tightness: f64,
+    #[arg(long, default_value_t = 10)]
+    min_care_time: u32,
+    #[arg(long, default_value_t = 10)]
+    max_care_time: u32,
+    #[arg(long, default_value_t = 1)]
+    min_demand: u32,
+    #[arg(long, default_value_t = 30)]
+    max_demand: u32,
+    /// Nurse capacity
+    #[arg(long, default_value_t = 200)]
+    capacity: u32,
+    /// Number of nurses, estimated from the total demand when left out
+    #[arg(short, long)]
+    nurses: Option<u32>,
+    #[arg(long)]
+    seed: Option<u64>,
+}
+
+impl GenerateArgs {
+    fn options(&self) -> GeneratorOptions {
+        GeneratorOptions {
+            instance_name: self.output.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default(),
+            patients: self.patients,
+            layout: self.layout,
+            clusters: self.clusters,
+            grid_size: self.grid_size,
+            horizon: self.horizon,
+            time_window_tightness: self.tightness,
+            care_time: (self.min_care_time, self.max_care_time),
+            demand: (self.min_demand, self.max_demand),
+            capacity: self.capacity,
+            nurses: self.nurses,
+            seed: self.seed,
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+struct RunArgs {
+    /// Config file
+    #[arg(short, long, default_value = "config/config.yaml")]
+    config: String,
+    /// Instance file, instead of train/<file_name>.json
+    #[arg(short, long)]
+    instance: Option<String>,
+    /// Folder for saved individuals
+    #[arg(short, long)]
+    output: Option<String>,
+    /// Override a config field, e.g. --set run_time=60. Can be repeated
+    #[arg(short = 's', long = "set", value_name = "KEY=VALUE")]
+    overrides: Vec<String>,
+}
+
+impl RunArgs {
+    fn config(&self) -> Result<Config, Box<dyn Error>> {
+        let mut config = Config::from_file(&self.config, &self.overrides)?;
+        if let Some(instance) = &self.instance {
+            // Saved individuals are grouped by instance name
+            config.file_name = Path::new(instance)
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .ok_or_else(|| format!("Invalid instance path {}", instance))?
+                .to_string();
+            config.instance_path = Some(instance.clone());
+        }
+        if let Some(output) = &self.output {
+            config.output_dir = output.clone();
+        }
+        Ok(config)
+    }
+}
+
+pub fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
+    let command = cli.command.unwrap_or(Command::Solve(RunArgs {
+        config: "config/config.yaml".to_string(),
+        instance: None,
+        output: None,
+        overrides: Vec::new(),
+    }));
+
+    match command {
+        Command::Solve(run) => {
+            let config = run.config()?;
+            if config.print_and_graph {
+                println!("Starting plotting");
+                plot_best_individual(&config, "plots")?;
+                println!("Finished plotting");
+            }
+            let info = read_instance(&config)?;
+            let benchmark = info.benchmark;
+            let save_info = info.clone();
+            let save_config = config.clone();
+            let run_id = new_run_id();
+            println!("Run id: {}", run_id);
+            let result = Solver::builder(info, config)
+                .on_improvement(move |individual| {
+                    let mut individual = individual.clone();
+                    if save_config.polish_before_saving {
+                        polish(&mut individual, &save_info, &save_config);
+                    }
+                    // Only save good solutions that are within the benchmark range
+                    if individual.fitness < benchmark * 1.05 {
+                        save_individual(&individual, &save_info, &save_config, &run_id);
+                    }
+                })
+                .build()?
+                .solve();
+            println!(
+                "Best fitness: {} Feasible: {} Generations: {} Execution_time {:?}",
+                result.best.fitness, result.best.feasible, result.stats.generations, result.stats.elapsed
+            );
+        }
+        Command::Plot { run, solution, plot_dir } => {
+            let config = run.config()?;
+            match solution {
+                Some(solution) => plot_individual_file(&solution, &read_instance(&config)?, &plot_dir)?,
+                None => plot_best_individual(&config, &plot_dir)?,
+            }
+        }
+        Command::Evaluate { run, solution } => {
+            let config = run.config()?;
+            let info = read_instance(&config)?;
+            let nurses = load_nurses(&solution, &info)?;
+            let mut fitness = 0.;
+            let mut feasible = true;
+            for nurse in &nurses {
+                let (nurse_fitness, nurse_feasible) = fitness_nurse(nurse, &info, &config);
+                fitness += nurse_fitness;
+                feasible &= nurse_feasible;
+            }
+            print_best_solution(read_individual(&solution)?, &info);
+            println!("Fitness: {:.4}", fitness);
+            println!("Feasible: {}", feasible);
+        }
+        Command::Validate { run, solution } => {
+            let config = run.config()?;
+            let info = read_instance(&config)?;
+            let report = validate_solution(&read_individual(&solution)?, &info);
+            for violation in &report.violations {
+                println!("{}", violation);
+            }
+            println!("Nurses used: {}", report.nurses_used);
+            println!("Total travel time: {:.4}", report.total_travel_time);
+
+            if !report.is_valid() {
+                return Err(format!("{} is not a valid solution: {} violations", solution.display(), report.violations.len()).into());
+            }
+            println!("{} is a valid solution", solution.display());
+        }
+        Command::Generate(args) => {
+            let instance = generate_instance(&args.options())?;
+            write_instance(&instance, &args.output)?;
+            println!(
+                "Wrote {} with {} patients, {} nurses and benchmark {}",
+                args.output.display(), instance.patients.len(), instance.nbr_nurses, instance.benchmark
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Read a saved solution into zero-indexed nurses, rejecting unknown patients.
+fn load_nurses(path: &Path, info: &Info) -> Result<Vec<Nurse>, Box<dyn Error>> {
+    let mut nurses = Vec::new();
+    for route in read_individual(path)? {
+        for patient in &route {
+            if *patient < 1 || *patient as usize > info.patients.len() {
+                return Err(format!("Unknown patient {} in {}", patient, path.display()).into());
+            }
+        }
+        nurses.push(Nurse { capacity: 0, route: route.iter().map(|p| p - 1).collect() });
+    }
+    if nurses.len() > info.nbr_nurses as usize {
+        return Err(format!("{} uses {} nurses, but only {} are available", path.display(), nurses.len(), info.nbr_nurses).into());
+    }
+    Ok(nurses)
+}
@@ -1,70 +1,75 @@
 use crate::structs::io::{Info, Patient};
-use crate::structs::nurse::Individual;
-use crate::genetic::evaluate::fitness_nurse;
-use crate::structs::config::{Config, CrossoverFN};
+use crate::structs::nurse::{Individual, Nurse};
+use crate::genetic::route_cache::RouteCache;
+use crate::genetic::operators::Crossover;
+use crate::genetic::random::GaRng;
+use crate::genetic::split::{giant_tour, split};
+use crate::structs::config::Config;
 
-use rand::Rng;
-use rand::rngs::ThreadRng;
+use rand::seq::{IndexedRandom, SliceRandom};
+use rand::{Rng, RngCore, SeedableRng};
 use rayon::slice::ParallelSlice;
-use rayon::iter::ParallelIterator;
-
-type CrossoverFNType = fn(&Individual, &Individual, &Info, &Config) -> (Individual, Individual);
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
 
 pub fn population_crossover(
-    population: &mut Vec<Individual>,
-    parent_indices: &Vec<usize>,
+    population: &mut [Individual],
+    parent_indices: &[usize],
+    rng: &mut GaRng,
     info: &Info,
     config: &Config,
+    crossover: &dyn Crossover,
 ) -> Vec<Individual> {
-    let crossover_fn: CrossoverFNType = match config.crossover_fn {
-        CrossoverFN::Visma =>  visma_crossover,
-    };
+    // One seed per pair of parents, so the result does not depend on how the work is split between threads
+    let seeds: Vec<u64> = parent_indices.chunks_exact(2).map(|_| rng.next_u64()).collect();
 
     // Could make this shorter, but don't care.
-    let children: Vec<Individual>;
-    if config.use_islands {
-        children = parent_indices
+    let children: Vec<Individual> = if config.use_islands {
+        parent_indices
             .chunks_exact(2)
-            .flat_map(|parents| {
+            .zip(seeds.iter())
+            .flat_map(|(parents, seed)| {
                 let (parent1_idx, parent2_idx) = (parents[0], parents[1]);
-                let (child_1, child_2) = crossover_fn(
+                let (child_1, child_2) = crossover.crossover(
                     &population[parent1_idx],
                     &population[parent2_idx],
-                    &info,
-                    &config
+                    &mut GaRng::seed_from_u64(*seed),
+                    info,
+                    config
                 );
                 vec![child_1, child_2]
             })
-            .collect();
+            .collect()
     } else {
-        children = parent_indices
+        parent_indices
             .par_chunks_exact(2)
-            .flat_map(|parents| {
+            .zip(seeds.par_iter())
+            .flat_map(|(parents, seed)| {
                 let (parent1_idx, parent2_idx) = (parents[0], parents[1]);
-                let (child_1, child_2) = crossover_fn(
+                let (child_1, child_2) = crossover.crossover(
                     &population[parent1_idx],
                     &population[parent2_idx],
-                    &info,
-                    &config
+                    &mut GaRng::seed_from_u64(*seed),
+                    info,
+                    config
                 );
                 vec![child_1, child_2]
             })
-            .collect();
-    }
+            .collect()
+    };
 
     children
 }
 
 
-fn visma_crossover(
+pub fn visma_crossover(
     parent1: &Individual,
     parent2: &Individual,
+    rng: &mut GaRng,
     info: &Info,
     config: &Config
 ) -> (Individual, Individual) {
     let mut child1: Individual = parent1.clone();
     let mut child2: Individual = parent2.clone();
-    let mut rng: ThreadRng = rand::rng();
     if rng.random_range(0.0..=1.) > config.crossover_rate {
         return (parent1.clone(), parent2.clone())
     }
@@ -90,11 +95,11 @@ fn visma_crossover(
     }
     if !found { return (parent1.clone(), parent2.clone()); }
 
-    let repair_nurse_idx_1: usize = remove_crossover(&mut child1, &parent2, parent_idx_2);
-    let repair_nurse_idx_2: usize = remove_crossover(&mut child2, &parent1, parent_idx_1);
+    let repair_nurse_idx_1: usize = remove_crossover(&mut child1, parent2, parent_idx_2);
+    let repair_nurse_idx_2: usize = remove_crossover(&mut child2, parent1, parent_idx_1);
 
-    repair_nurse(&mut child1, &parent2, parent_idx_2, repair_nurse_idx_1, &info, &config);
-    repair_nurse(&mut child2, &parent1, parent_idx_1, repair_nurse_idx_2, &info, &config);
+    repair_nurse(&mut child1, parent2, parent_idx_2, repair_nurse_idx_1, info, config);
+    repair_nurse(&mut child2, parent1, parent_idx_1, repair_nurse_idx_2, info, config);
 
     (child1, child2)
 }
@@ -114,6 +119,11 @@ fn repair_nurse(
     }
     patients.sort_by_key(|p| (p.1.start_time, p.1.end_time));
 
+    let mut caches: Vec<RouteCache> = to_repair.nurses
+        .iter()
+        .map(|nurse| RouteCache::new(&nurse.route, info, config))
+        .collect();
+
     // Iterate over all nurses to find the best insertion point for each patient
     for patient in &patients {
         let mut lowest_fitness_change = f32::INFINITY;
@@ -121,17 +131,14 @@ fn repair_nurse(
         let mut best_insertion_idx = 0;
 
         // Check all nurses for the best insertion point
-        for (n_idx, nurse) in to_repair.nurses.iter_mut().enumerate() {
+        for (n_idx, (nurse, cache)) in to_repair.nurses.iter().zip(&caches).enumerate() {
             for insertion_idx in 0..=nurse.route.len() {
-                let old_fitness = fitness_nurse(nurse, &info, &config).0;
-                nurse.route.insert(insertion_idx, patient.0 as i32);
-                let new_fitness = fitness_nurse(nurse, &info, &config).0;
-                if new_fitness - old_fitness < lowest_fitness_change {
-                    lowest_fitness_change = new_fitness - old_fitness;
+                let fitness_change = cache.insert_delta(&nurse.route, insertion_idx, &[patient.0 as i32], info, config);
+                if fitness_change < lowest_fitness_change {
+                    lowest_fitness_change = fitness_change;
                     best_nurse_idx = n_idx;
                     best_insertion_idx = insertion_idx;
                 }
-                nurse.route.remove(insertion_idx); // Revert the insertion for now
             }
         }
 
@@ -139,6 +146,7 @@ fn repair_nurse(
         to_repair.nurses[best_nurse_idx]
             .route
             .insert(best_insertion_idx, patient.0 as i32);
+        caches[best_nurse_idx] = RouteCache::new(&to_repair.nurses[best_nurse_idx].route, info, config);
     }
 }
 
@@ -166,3 +174,270 @@ fn remove_crossover(individual_to_change: &mut Individual, other_individual: &In
     insertion_nurses.iter().max_by(|a, b| a.1.cmp(&b.1)).unwrap().0
 }
 
+/// Order crossover (OX) on the giant tours of the parents. A child keeps a random slice of one
+/// parent's tour and gets the other patients in the order of the other parent. Routes come from
+/// `split`.
+pub fn order_crossover(
+    parent1: &Individual,
+    parent2: &Individual,
+    rng: &mut GaRng,
+    info: &Info,
+    config: &Config
+) -> (Individual, Individual) {
+    if rng.random_range(0.0..=1.) > config.crossover_rate {
+        return (parent1.clone(), parent2.clone())
+    }
+    let (tour1, tour2) = (giant_tour(parent1), giant_tour(parent2));
+    if tour1.len() < 2 {
+        return (parent1.clone(), parent2.clone())
+    }
+    let start = rng.random_range(0..tour1.len());
+    let end = rng.random_range(start..tour1.len());
+
+    let child1 = order_crossover_tour(&tour1, &tour2, start, end, info);
+    let child2 = order_crossover_tour(&tour2, &tour1, start, end, info);
+    (tour_child(&child1, info, config), tour_child(&child2, info, config))
+}
+
+/// `kept[start..=end]` in place, the rest filled in from `other` starting after `end`
+pub fn order_crossover_tour(kept: &[i32], other: &[i32], start: usize, end: usize, info: &Info) -> Vec<i32> {
+    let n = kept.len();
+    let mut in_child = vec![false; info.patients.len()];
+    let mut child = vec![0; n];
+    for i in start..=end {
+        child[i] = kept[i];
+        in_child[kept[i] as usize] = true;
+    }
+
+    let mut position = (end + 1) % n;
+    for i in 0..other.len() {
+        let p = other[(end + 1 + i) % other.len()];
+        if in_child[p as usize] {
+            continue;
+        }
+        child[position] = p;
+        in_child[p as usize] = true;
+        position = (position + 1) % n;
+    }
+    child
+}
+
+/// Edge recombination crossover (ERX) on the giant tours of the parents. A child is built by
+/// going to a neighbour of the last patient in either parent's tour, preferring the neighbour
+/// with the fewest neighbours left. Routes come from `split`.
+pub fn edge_recombination_crossover(
+    parent1: &Individual,
+    parent2: &Individual,
+    rng: &mut GaRng,
+    info: &Info,
+    config: &Config
+) -> (Individual, Individual) {
+    if rng.random_range(0.0..=1.) > config.crossover_rate {
+        return (parent1.clone(), parent2.clone())
+    }
+    let (tour1, tour2) = (giant_tour(parent1), giant_tour(parent2));
+    if tour1.is_empty() || tour2.is_empty() {
+        return (parent1.clone(), parent2.clone())
+    }
+
+    // Both tours are read as cycles, each patient has at most four neighbours
+    let mut edges: Vec<Vec<i32>> = vec![Vec::with_capacity(4); info.patients.len()];
+    for tour in [&tour1, &tour2] {
+        for (i, &p) in tour.iter().enumerate() {
+            for neighbour in [tour[(i + tour.len() - 1) % tour.len()], tour[(i + 1) % tour.len()]] {
+                if neighbour != p && !edges[p as usize].contains(&neighbour) {
+                    edges[p as usize].push(neighbour);
+                }
+            }
+        }
+    }
+
+    let child1 = edge_recombination_tour(tour1[0], &tour1, &edges, rng, info);
+    let child2 = edge_recombination_tour(tour2[0], &tour2, &edges, rng, info);
+    (tour_child(&child1, info, config), tour_child(&child2, info, config))
+}
+
+fn edge_recombination_tour(first: i32, patients: &[i32], edges: &[Vec<i32>], rng: &mut GaRng, info: &Info) -> Vec<i32> {
+    let mut visited = vec![false; info.patients.len()];
+    let mut child = Vec::with_capacity(patients.len());
+    let unvisited_neighbours = |p: i32, visited: &[bool]| edges[p as usize].iter().filter(|n| !visited[**n as usize]).count();
+
+    let mut current = first;
+    loop {
+        child.push(current);
+        visited[current as usize] = true;
+        if child.len() == patients.len() {
+            return child;
+        }
+
+        let candidates: Vec<i32> = edges[current as usize].iter().copied().filter(|n| !visited[*n as usize]).collect();
+        let fewest = candidates.iter().map(|n| unvisited_neighbours(*n, &visited)).min();
+        let next: Vec<i32> = match fewest {
+            Some(fewest) => candidates.into_iter().filter(|n| unvisited_neighbours(*n, &visited) == fewest).collect(),
+            // Dead end, start over from any patient left
+            None => patients.iter().copied().filter(|p| !visited[*p as usize]).collect(),
+        };
+        current = *next.choose(rng).unwrap();
+    }
+}
+
+fn tour_child(tour: &[i32], info: &Info, config: &Config) -> Individual {
+    Individual { nurses: split(tour, info, config), fitness: 0., feasible: false }
+}
+
+/// A child gets a random half of the routes of one parent, then every route of the other parent
+/// that does not visit any of those patients. Patients left over are inserted where they cost
+/// the least.
+pub fn route_based_crossover(
+    parent1: &Individual,
+    parent2: &Individual,
+    rng: &mut GaRng,
+    info: &Info,
+    config: &Config
+) -> (Individual, Individual) {
+    if rng.random_range(0.0..=1.) > config.crossover_rate {
+        return (parent1.clone(), parent2.clone())
+    }
+    let child1 = route_based_child(parent1, parent2, rng, info, config);
+    let child2 = route_based_child(parent2, parent1, rng, info, config);
+    (child1, child2)
+}
+
+fn route_based_child(
+    parent1: &Individual,
+    parent2: &Individual,
+    rng: &mut GaRng,
+    info: &Info,
+    config: &Config
+) -> Individual {
+    let mut visited = vec![false; info.patients.len()];
+    let mut nurses: Vec<Nurse> = Vec::with_capacity(parent1.nurses.len());
+
+    for nurse in parent1.nurses.iter().filter(|nurse| !nurse.route.is_empty()) {
+        if rng.random_bool(0.5) {
+            nurse.route.iter().for_each(|p| visited[*p as usize] = true);
+            nurses.push(nurse.clone());
+        }
+    }
+    let mut routes2: Vec<&Nurse> = parent2.nurses.iter().filter(|nurse| !nurse.route.is_empty()).collect();
+    routes2.shuffle(rng);
+    for nurse in routes2 {
+        if nurses.len() < parent1.nurses.len() && nurse.route.iter().all(|p| !visited[*p as usize]) {
+            nurse.route.iter().for_each(|p| visited[*p as usize] = true);
+            nurses.push(nurse.clone());
+        }
+    }
+    nurses.resize(parent1.nurses.len(), Nurse::new());
+
+    let left_over: Vec<i32> = giant_tour(parent1).into_iter().filter(|p| !visited[*p as usize]).collect();
+    insert_patients(&mut nurses, left_over, info, config);
+
+    Individual { nurses, fitness: 0., feasible: false }
+}
+
+/// Insert every patient, by start of their time window, where it costs the least
+fn insert_patients(nurses: &mut [Nurse], mut patients: Vec<i32>, info: &Info, config: &Config) {
+    patients.sort_by_key(|p| (info.patients[*p as usize].start_time, info.patients[*p as usize].end_time));
+    let mut caches: Vec<RouteCache> = nurses.iter().map(|nurse| RouteCache::new(&nurse.route, info, config)).collect();
+    for p in patients {
+        let mut best = (f32::INFINITY, 0, 0);
+        for (n_idx, (nurse, cache)) in nurses.iter().zip(&caches).enumerate() {
+            for insertion_idx in 0..=nurse.route.len() {
+                let fitness_change = cache.insert_delta(&nurse.route, insertion_idx, &[p], info, config);
+                if fitness_change < best.0 {
+                    best = (fitness_change, n_idx, insertion_idx);
+                }
+            }
+        }
+        let (_, n_idx, insertion_idx) = best;
+        nurses[n_idx].route.insert(insertion_idx, p);
+        caches[n_idx] = RouteCache::new(&nurses[n_idx].route, info, config);
+    }
+}
+
+/// Selective route exchange crossover (SREX, Nagata et al. 2010). A few neighbouring routes, by the
+/// angle of their centre around the depot, are picked in both parents. Each child is one parent with
+/// its picked routes replaced by those of the other parent. Patients visited twice are removed from
+/// the kept routes and patients no longer visited are inserted where they cost the least.
+pub fn srex_crossover(
+    parent1: &Individual,
+    parent2: &Individual,
+    rng: &mut GaRng,
+    info: &Info,
+    config: &Config
+) -> (Individual, Individual) {
+    if rng.random_range(0.0..=1.) > config.crossover_rate {
+        return (parent1.clone(), parent2.clone())
+    }
+    let routes1 = routes_by_angle(parent1, info);
+    let routes2 = routes_by_angle(parent2, info);
+    if routes1.is_empty() || routes2.is_empty() {
+        return (parent1.clone(), parent2.clone())
+    }
+
+    let nbr_exchanged = rng.random_range(1..=(routes1.len().min(routes2.len()) / 2).max(1));
+    let start1 = rng.random_range(0..routes1.len());
+    // Start in the second parent at the route pointing the same way
+    let start2 = (0..routes2.len())
+        .min_by(|a, b| {
+            angle_between(routes2[*a].1, routes1[start1].1).total_cmp(&angle_between(routes2[*b].1, routes1[start1].1))
+        })
+        .unwrap();
+    let picked1: Vec<usize> = (0..nbr_exchanged).map(|i| routes1[(start1 + i) % routes1.len()].0).collect();
+    let picked2: Vec<usize> = (0..nbr_exchanged).map(|i| routes2[(start2 + i) % routes2.len()].0).collect();
+
+    let child1 = exchange_routes(parent1, &picked1, parent2, &picked2, info, config);
+    let child2 = exchange_routes(parent2, &picked2, parent1, &picked1, info, config);
+    (child1, child2)
+}
+
+/// Index and angle around the depot of the centre of every non-empty route, sorted by angle
+fn routes_by_angle(individual: &Individual, info: &Info) -> Vec<(usize, f64)> {
+    let mut routes: Vec<(usize, f64)> = individual.nurses.iter().enumerate()
+        .filter(|(_, nurse)| !nurse.route.is_empty())
+        .map(|(i, nurse)| {
+            let n = nurse.route.len() as f64;
+            let x = nurse.route.iter().map(|p| info.patients[*p as usize].x_coord).sum::<f64>() / n;
+            let y = nurse.route.iter().map(|p| info.patients[*p as usize].y_coord).sum::<f64>() / n;
+            (i, (y - info.depot.y_coord).atan2(x - info.depot.x_coord))
+        })
+        .collect();
+    routes.sort_by(|a, b| a.1.total_cmp(&b.1));
+    routes
+}
+
+fn angle_between(a: f64, b: f64) -> f64 {
+    let difference = (a - b).abs() % std::f64::consts::TAU;
+    difference.min(std::f64::consts::TAU - difference)
+}
+
+/// `base` with the routes `removed` swapped for the routes `added` of `donor`
+fn exchange_routes(
+    base: &Individual,
+    removed: &[usize],
+    donor: &Individual,
+    added: &[usize],
+    info: &Info,
+    config: &Config
+) -> Individual {
+    let mut from_donor = vec![false; info.patients.len()];
+    for &i in added {
+        donor.nurses[i].route.iter().for_each(|p| from_donor[*p as usize] = true);
+    }
+
+    let mut nurses: Vec<Nurse> = Vec::with_capacity(base.nurses.len());
+    let mut left_over = Vec::new();
+    for (i, nurse) in base.nurses.iter().enumerate() {
+        if removed.contains(&i) {
+            left_over.extend(nurse.route.iter().copied().filter(|p| !from_donor[*p as usize]));
+        } else if !nurse.route.is_empty() {
+            let route: Vec<i32> = nurse.route.iter().copied().filter(|p| !from_donor[*p as usize]).collect();
+            nurses.push(Nurse { capacity: nurse.capacity, route });
+        }
+    }
+    nurses.extend(added.iter().map(|i| donor.nurses[*i].clone()));
+    nurses.resize(base.nurses.len(), Nurse::new());
+    insert_patients(&mut nurses, left_over, info, config);
+
+    Individual { nurses, fitness: 0., feasible: false }
+}
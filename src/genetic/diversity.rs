@@ -0,0 +1,125 @@
+use rand::Rng;
+
+use crate::genetic::random::GaRng;
+use crate::structs::config::Config;
+use crate::structs::nurse::Individual;
+
+/// Successor and predecessor of every patient in one individual, where patient `p` is node
+/// `p + 1` and the depot is node 0. Built once per individual, so the distance to many others
+/// is one pass over the patients each.
+#[derive(Debug, Clone)]
+pub struct Neighbours {
+    successors: Vec<u32>,
+    predecessors: Vec<u32>,
+}
+
+impl Neighbours {
+    pub fn new(individual: &Individual) -> Self {
+        let nbr_patients = individual.nurses.iter().map(|nurse| nurse.route.len()).sum();
+        let mut successors = vec![0; nbr_patients];
+        let mut predecessors = vec![0; nbr_patients];
+        for nurse in &individual.nurses {
+            let mut previous = 0;
+            for &p in &nurse.route {
+                let p = p as usize;
+                if p >= successors.len() {
+                    successors.resize(p + 1, 0);
+                    predecessors.resize(p + 1, 0);
+                }
+                predecessors[p] = previous;
+                if previous != 0 {
+                    successors[previous as usize - 1] = p as u32 + 1;
+                }
+                previous = p as u32 + 1;
+            }
+        }
+        Neighbours { successors, predecessors }
+    }
+
+    /// Broken-pairs distance, the share of patients whose neighbours differ, from 0 for the same
+    /// routes (in any order or direction) to 1 for no shared edges. A patient counts as broken
+    /// when its successor here is neither its successor nor its predecessor in `other`, or when
+    /// it starts a route here but is inside a route in `other`, and counts once if both hold.
+    pub fn distance(&self, other: &Neighbours) -> f32 {
+        let nbr_patients = self.successors.len().max(other.successors.len());
+        if nbr_patients == 0 {
+            return 0.;
+        }
+        let get = |array: &Vec<u32>, p: usize| array.get(p).copied().unwrap_or(0);
+
+        let mut broken = 0;
+        for p in 0..nbr_patients {
+            let successor = get(&self.successors, p);
+            let (other_successor, other_predecessor) = (get(&other.successors, p), get(&other.predecessors, p));
+            let successor_broken = successor != other_successor && successor != other_predecessor;
+            let start_broken = get(&self.predecessors, p) == 0 && other_predecessor != 0 && other_successor != 0;
+            if successor_broken || start_broken {
+                broken += 1;
+            }
+        }
+        broken as f32 / nbr_patients as f32
+    }
+}
+
+/// Broken-pairs distance between two individuals, see `Neighbours::distance`
+pub fn broken_pairs_distance(a: &Individual, b: &Individual) -> f32 {
+    Neighbours::new(a).distance(&Neighbours::new(b))
+}
+
+/// Biased fitness of every individual, lower is better. Combines the rank by fitness with the rank
+/// by diversity contribution, the average broken-pairs distance to the `diversity_neighbours`
+/// closest individuals. The best `biased_fitness_elite` individuals by fitness stay on top.
+pub fn biased_fitness(population: &[Individual], config: &Config) -> Vec<f32> {
+    let size = population.len();
+    if size < 2 {
+        return vec![0.; size];
+    }
+
+    let neighbours: Vec<Neighbours> = population.iter().map(Neighbours::new).collect();
+    let mut distances = vec![vec![0.; size]; size];
+    for i in 0..size {
+        for j in i + 1..size {
+            let distance = neighbours[i].distance(&neighbours[j]);
+            distances[i][j] = distance;
+            distances[j][i] = distance;
+        }
+    }
+    let n_closest = (config.diversity_neighbours.max(1) as usize).min(size - 1);
+    let diversity: Vec<f32> = (0..size)
+        .map(|i| {
+            let mut others: Vec<f32> = (0..size).filter(|&j| j != i).map(|j| distances[i][j]).collect();
+            others.sort_by(f32::total_cmp);
+            others[..n_closest].iter().sum::<f32>() / n_closest as f32
+        })
+        .collect();
+
+    let fitness_rank = normalised_ranks(size, |a, b| population[a].fitness.total_cmp(&population[b].fitness));
+    // Most diverse first
+    let diversity_rank = normalised_ranks(size, |a, b| diversity[b].total_cmp(&diversity[a]));
+    let diversity_weight = (1. - config.biased_fitness_elite as f32 / size as f32).max(0.);
+
+    (0..size).map(|i| fitness_rank[i] + diversity_weight * diversity_rank[i]).collect()
+}
+
+/// Rank of every index in `0..size` under `order`, scaled to [0, 1]
+fn normalised_ranks(size: usize, order: impl Fn(usize, usize) -> std::cmp::Ordering) -> Vec<f32> {
+    let mut indices: Vec<usize> = (0..size).collect();
+    indices.sort_by(|a, b| order(*a, *b));
+    let mut ranks = vec![0.; size];
+    for (rank, index) in indices.into_iter().enumerate() {
+        ranks[index] = rank as f32 / (size - 1) as f32;
+    }
+    ranks
+}
+
+/// Parent indices picked by binary tournaments on biased fitness
+pub fn biased_tournament(population: &[Individual], rng: &mut GaRng, config: &Config) -> Vec<usize> {
+    let biased = biased_fitness(population, config);
+    let n_parents = (population.len().saturating_sub(config.n_elitism as usize) as f32 * config.n_parents_scaling) as usize;
+    (0..n_parents)
+        .map(|_| {
+            let (a, b) = (rng.random_range(0..population.len()), rng.random_range(0..population.len()));
+            if biased[b] < biased[a] { b } else { a }
+        })
+        .collect()
+}
@@ -0,0 +1,461 @@
+use std::time::{Duration, Instant};
+
+use rayon::iter::{IntoParallelRefMutIterator, ParallelIterator};
+
+use crate::genetic::evaluate::Segment;
+use crate::genetic::granular::Granular;
+use crate::genetic::route_cache::RouteCache;
+use crate::structs::config::{Config, Improvement, Neighbourhood};
+use crate::structs::io::Info;
+use crate::structs::nurse::{Individual, Nurse};
+
+/// Smallest decrease in cost counted as an improvement, so rounding errors can not cycle
+const MIN_IMPROVEMENT: f32 = 1e-4;
+
+/// Longest segment moved by Or-opt
+const MAX_SEGMENT_LENGTH: usize = 3;
+
+/// Routes to replace, by nurse index, when a move is applied
+type Move = Vec<(usize, Vec<i32>)>;
+
+/// Nurse and position of a patient, and of one of its granular neighbours
+type Pair = (usize, usize, usize, usize);
+
+/// Educate every child, see `educate`. With `granular` the moves are restricted to granular
+/// neighbourhoods
+pub fn educate_population(population: &mut [Individual], info: &Info, config: &Config, granular: Option<&Granular>) {
+    if config.use_islands {
+        population.iter_mut().for_each(|individual| { educate(&mut individual.nurses, info, config, granular); });
+    } else {
+        population.par_iter_mut().for_each(|individual| { educate(&mut individual.nurses, info, config, granular); });
+    }
+}
+
+/// Variable neighbourhood descent over `education_neighbourhoods`: apply an improving move of the
+/// first neighbourhood that has one and start over from the first, until none improves or the
+/// budget of `education_max_moves` and `education_time_limit` runs out. Returns the number of
+/// moves applied.
+///
+/// With `granular` only moves that make a patient and one of its granular neighbours follow each
+/// other are tried (for Swap, that put them in each other's place), so one sweep over a
+/// neighbourhood takes O(nk) instead of O(n²) moves.
+pub fn educate(nurses: &mut [Nurse], info: &Info, config: &Config, granular: Option<&Granular>) -> usize {
+    let started = Instant::now();
+    let time_limit = Duration::from_millis(config.education_time_limit);
+    let mut caches: Vec<RouteCache> = nurses.iter().map(|nurse| RouteCache::new(&nurse.route, info, config)).collect();
+
+    let mut pairs = granular.map(|granular| granular_pairs(nurses, granular, info));
+    let mut moves = 0;
+    let mut k = 0;
+    while k < config.education_neighbourhoods.len() {
+        if moves >= config.education_max_moves || (config.education_time_limit > 0 && started.elapsed() >= time_limit) {
+            break;
+        }
+
+        let mut search = Search::new(nurses, &caches, info, config);
+        match &pairs {
+            Some(pairs) => search.run_granular(config.education_neighbourhoods[k], pairs),
+            None => search.run(config.education_neighbourhoods[k]),
+        }
+        match search.best {
+            Some((_, improving)) => {
+                for (nurse_idx, route) in improving {
+                    caches[nurse_idx] = RouteCache::new(&route, info, config);
+                    nurses[nurse_idx].route = route;
+                }
+                if let Some(granular) = granular {
+                    pairs = Some(granular_pairs(nurses, granular, info));
+                }
+                moves += 1;
+                k = 0;
+            }
+            None => k += 1,
+        }
+    }
+    moves
+}
+
+/// Apply the first improving move of `neighbourhoods` that stays within `route`, leaving out moves
+/// that make a feasible route late or over capacity. Relocate then moves single patients, and
+/// 2-opt* and cross-exchange have nothing to work with. Returns whether the route changed
+pub fn improve_route(route: &mut [i32], neighbourhoods: &[Neighbourhood], info: &Info, config: &Config) -> bool {
+    let nurses = [Nurse { capacity: 0, route: route.to_vec() }];
+    let caches = [RouteCache::new(route, info, config)];
+    let mut search = Search { improvement: Improvement::First, keep_feasible: true, ..Search::new(&nurses, &caches, info, config) };
+    for neighbourhood in neighbourhoods {
+        search.run(*neighbourhood);
+        if let Some((_, mut changed)) = search.best {
+            route.copy_from_slice(&changed.pop().unwrap().1);
+            return true;
+        }
+    }
+    false
+}
+
+/// Every patient with every granular neighbour of it
+fn granular_pairs(nurses: &[Nurse], granular: &Granular, info: &Info) -> Vec<Pair> {
+    let mut positions = vec![None; info.patients.len()];
+    for (a, nurse) in nurses.iter().enumerate() {
+        for (i, p) in nurse.route.iter().enumerate() {
+            positions[*p as usize] = Some((a, i));
+        }
+    }
+    let mut pairs = Vec::new();
+    for (u, position) in positions.iter().enumerate() {
+        let Some((a, i)) = position else { continue };
+        for v in granular.neighbours(u as i32) {
+            if let Some((b, j)) = positions[*v as usize] {
+                pairs.push((*a, *i, b, j));
+            }
+        }
+    }
+    pairs
+}
+
+/// Looks for an improving move in one neighbourhood
+struct Search<'a> {
+    nurses: &'a [Nurse],
+    caches: &'a [RouteCache],
+    info: &'a Info,
+    config: &'a Config,
+    improvement: Improvement,
+    /// Leave out moves that make a feasible route infeasible
+    keep_feasible: bool,
+    best: Option<(f32, Move)>,
+}
+
+impl<'a> Search<'a> {
+    fn new(nurses: &'a [Nurse], caches: &'a [RouteCache], info: &'a Info, config: &'a Config) -> Self {
+        Search { nurses, caches, info, config, improvement: config.education_improvement, keep_feasible: false, best: None }
+    }
+
+    fn run(&mut self, neighbourhood: Neighbourhood) {
+        match neighbourhood {
+            Neighbourhood::Relocate => self.relocate(),
+            Neighbourhood::Swap => self.swap(),
+            Neighbourhood::TwoOpt => self.two_opt(),
+            Neighbourhood::TwoOptStar => self.two_opt_star(),
+            Neighbourhood::OrOpt => self.or_opt(),
+            Neighbourhood::CrossExchange => self.cross_exchange(),
+        }
+    }
+
+    /// Like `run`, but only for moves that bring the patients of a pair next to each other
+    fn run_granular(&mut self, neighbourhood: Neighbourhood, pairs: &[Pair]) {
+        for &(a, i, b, j) in pairs {
+            let stop = match neighbourhood {
+                Neighbourhood::Relocate => self.relocate_next_to(a, i, b, j),
+                Neighbourhood::Swap => self.swap_pair(a, i, b, j),
+                Neighbourhood::TwoOpt => a == b && self.two_opt_pair(a, i, j),
+                Neighbourhood::TwoOptStar => a != b && self.two_opt_star_pair(a, i, b, j),
+                Neighbourhood::OrOpt => a == b && (2..=MAX_SEGMENT_LENGTH).any(|length| self.or_opt_pair(a, i, j, length)),
+                Neighbourhood::CrossExchange => a != b && self.cross_exchange_pair(a, i, b, j),
+            };
+            if stop {
+                return;
+            }
+        }
+        // Granular neighbours never move a patient into an empty route
+        if neighbourhood == Neighbourhood::Relocate {
+            self.relocate_to_empty_route();
+        }
+    }
+
+    /// Keep the move if it improves on the best so far. Returns true when the search can stop
+    fn consider(&mut self, delta: f32, build: impl FnOnce() -> Move) -> bool {
+        if delta < -MIN_IMPROVEMENT && self.best.as_ref().is_none_or(|(best, _)| delta < *best) {
+            let changed = build();
+            if !self.keep_feasible || self.stays_feasible(&changed) {
+                self.best = Some((delta, changed));
+            }
+        }
+        self.best.is_some() && self.improvement == Improvement::First
+    }
+
+    /// Whether every changed route that is feasible now stays feasible
+    fn stays_feasible(&self, changed: &Move) -> bool {
+        changed.iter().all(|(nurse_idx, route)| {
+            !self.caches[*nurse_idx].feasible || Segment::of_route(route, self.info).is_feasible(self.info)
+        })
+    }
+
+    /// Change in cost of the route of `nurse_idx` from replacing `[start..end]` with `replacement`
+    fn splice_delta(&self, nurse_idx: usize, start: usize, end: usize, replacement: &[i32]) -> f32 {
+        self.caches[nurse_idx].splice_delta(&self.nurses[nurse_idx].route, start, end, replacement, self.info, self.config)
+    }
+
+    /// Move one patient to any other position, in its own route or another
+    fn relocate(&mut self) {
+        let nurses = self.nurses;
+        for a in 0..nurses.len() {
+            for i in 0..nurses[a].route.len() {
+                if self.move_segment(a, i, 1) {
+                    return;
+                }
+                let p = nurses[a].route[i];
+                let removed = self.splice_delta(a, i, i + 1, &[]);
+                for b in (0..nurses.len()).filter(|b| *b != a) {
+                    for j in 0..=nurses[b].route.len() {
+                        let delta = removed + self.caches[b].insert_delta(&nurses[b].route, j, &[p], self.info, self.config);
+                        let build = || vec![(a, spliced(&nurses[a].route, i, i + 1, &[])), (b, spliced(&nurses[b].route, j, j, &[p]))];
+                        if self.consider(delta, build) {
+                            return;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Exchange two patients, in the same route or two routes
+    fn swap(&mut self) {
+        let nurses = self.nurses;
+        for a in 0..nurses.len() {
+            for i in 0..nurses[a].route.len() {
+                for j in i + 1..nurses[a].route.len() {
+                    let delta = self.caches[a].swap_delta(&nurses[a].route, i, j, self.info, self.config);
+                    let build = || {
+                        let mut route = nurses[a].route.to_vec();
+                        route.swap(i, j);
+                        vec![(a, route)]
+                    };
+                    if self.consider(delta, build) {
+                        return;
+                    }
+                }
+                for b in a + 1..nurses.len() {
+                    for j in 0..nurses[b].route.len() {
+                        let (p, q) = (nurses[a].route[i], nurses[b].route[j]);
+                        let delta = self.splice_delta(a, i, i + 1, &[q]) + self.splice_delta(b, j, j + 1, &[p]);
+                        let build = || vec![(a, spliced(&nurses[a].route, i, i + 1, &[q])), (b, spliced(&nurses[b].route, j, j + 1, &[p]))];
+                        if self.consider(delta, build) {
+                            return;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Reverse part of a route
+    fn two_opt(&mut self) {
+        let nurses = self.nurses;
+        for (a, nurse) in nurses.iter().enumerate() {
+            for i in 0..nurse.route.len() {
+                for j in i + 1..nurse.route.len() {
+                    let reversed: Vec<i32> = nurse.route[i..=j].iter().rev().copied().collect();
+                    let delta = self.splice_delta(a, i, j + 1, &reversed);
+                    if self.consider(delta, || vec![(a, spliced(&nurse.route, i, j + 1, &reversed))]) {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Exchange the ends of two routes
+    fn two_opt_star(&mut self) {
+        let nurses = self.nurses;
+        for a in 0..nurses.len() {
+            for b in a + 1..nurses.len() {
+                let (len_a, len_b) = (nurses[a].route.len(), nurses[b].route.len());
+                for i in 0..=len_a {
+                    for j in 0..=len_b {
+                        // Exchanging both whole routes, or nothing, changes nothing
+                        if (i == 0 && j == 0) || (i == len_a && j == len_b) {
+                            continue;
+                        }
+                        let (tail_a, tail_b) = (&nurses[a].route[i..], &nurses[b].route[j..]);
+                        let delta = self.splice_delta(a, i, len_a, tail_b) + self.splice_delta(b, j, len_b, tail_a);
+                        let build = || vec![(a, spliced(&nurses[a].route, i, len_a, tail_b)), (b, spliced(&nurses[b].route, j, len_b, tail_a))];
+                        if self.consider(delta, build) {
+                            return;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Move two or three consecutive patients elsewhere in their route
+    fn or_opt(&mut self) {
+        let nurses = self.nurses;
+        for (a, nurse) in nurses.iter().enumerate() {
+            for length in 2..=MAX_SEGMENT_LENGTH {
+                for i in 0..(nurse.route.len() + 1).saturating_sub(length) {
+                    if self.move_segment(a, i, length) {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Try `route[i..i + length]` at every other position of the same route
+    fn move_segment(&mut self, a: usize, i: usize, length: usize) -> bool {
+        (0..=self.nurses[a].route.len() - length).any(|j| self.move_segment_to(a, i, length, j))
+    }
+
+    /// Move `route[i..i + length]` to position `j` of the route without it
+    fn move_segment_to(&mut self, a: usize, i: usize, length: usize, j: usize) -> bool {
+        let nurses = self.nurses;
+        let route = &nurses[a].route;
+        let segment = &route[i..i + length];
+        let (start, end, replacement) = match j.cmp(&i) {
+            std::cmp::Ordering::Equal => return false,
+            std::cmp::Ordering::Less => (j, i + length, [segment, &route[j..i]].concat()),
+            std::cmp::Ordering::Greater => (i, j + length, [&route[i + length..j + length], segment].concat()),
+        };
+        let delta = self.splice_delta(a, start, end, &replacement);
+        self.consider(delta, || vec![(a, spliced(route, start, end, &replacement))])
+    }
+
+    /// Exchange up to `cross_exchange_max_length` consecutive patients of one route with up to as
+    /// many of another
+    fn cross_exchange(&mut self) {
+        let nurses = self.nurses;
+        for a in 0..nurses.len() {
+            for b in a + 1..nurses.len() {
+                for i in 0..nurses[a].route.len() {
+                    for j in 0..nurses[b].route.len() {
+                        if self.exchange_segments(a, i, b, j) {
+                            return;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Exchange segments of route `a` starting at `i` and of route `b` starting at `j`, of every
+    /// length up to `cross_exchange_max_length`, also reversed with `cross_exchange_reverse`
+    fn exchange_segments(&mut self, a: usize, i: usize, b: usize, j: usize) -> bool {
+        let nurses = self.nurses;
+        let (route_a, route_b) = (&nurses[a].route, &nurses[b].route);
+        let max_length = self.config.cross_exchange_max_length;
+        let reversals: &[bool] = if self.config.cross_exchange_reverse { &[false, true] } else { &[false] };
+        for length_a in 1..=max_length.min(route_a.len() - i) {
+            for length_b in 1..=max_length.min(route_b.len() - j) {
+                for &reverse_a in reversals {
+                    for &reverse_b in reversals {
+                        let segment_a = segment(route_a, i, length_a, reverse_a);
+                        let segment_b = segment(route_b, j, length_b, reverse_b);
+                        let delta = self.splice_delta(a, i, i + length_a, &segment_b) + self.splice_delta(b, j, j + length_b, &segment_a);
+                        let build = || vec![
+                            (a, spliced(route_a, i, i + length_a, &segment_b)),
+                            (b, spliced(route_b, j, j + length_b, &segment_a)),
+                        ];
+                        if self.consider(delta, build) {
+                            return true;
+                        }
+                    }
+                }
+            }
+        }
+        false
+    }
+
+    /// Move patient `u` at `(a, i)` right before and right after patient `v` at `(b, j)`
+    fn relocate_next_to(&mut self, a: usize, i: usize, b: usize, j: usize) -> bool {
+        if a == b {
+            // Position of `v` once `u` is taken out
+            let j = if j > i { j - 1 } else { j };
+            return self.move_segment_to(a, i, 1, j) || self.move_segment_to(a, i, 1, j + 1);
+        }
+        let nurses = self.nurses;
+        let u = nurses[a].route[i];
+        let removed = self.splice_delta(a, i, i + 1, &[]);
+        for position in [j, j + 1] {
+            let delta = removed + self.splice_delta(b, position, position, &[u]);
+            let build = || vec![(a, spliced(&nurses[a].route, i, i + 1, &[])), (b, spliced(&nurses[b].route, position, position, &[u]))];
+            if self.consider(delta, build) {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Move any patient into the first empty route
+    fn relocate_to_empty_route(&mut self) {
+        let nurses = self.nurses;
+        let Some(empty) = nurses.iter().position(|nurse| nurse.route.is_empty()) else { return };
+        for (a, nurse) in nurses.iter().enumerate() {
+            for (i, u) in nurse.route.iter().enumerate() {
+                let delta = self.splice_delta(a, i, i + 1, &[]) + self.splice_delta(empty, 0, 0, &[*u]);
+                if self.consider(delta, || vec![(a, spliced(&nurse.route, i, i + 1, &[])), (empty, vec![*u])]) {
+                    return;
+                }
+            }
+        }
+    }
+
+    fn swap_pair(&mut self, a: usize, i: usize, b: usize, j: usize) -> bool {
+        let nurses = self.nurses;
+        if a == b {
+            let delta = self.caches[a].swap_delta(&nurses[a].route, i, j, self.info, self.config);
+            let build = || {
+                let mut route = nurses[a].route.clone();
+                route.swap(i, j);
+                vec![(a, route)]
+            };
+            return self.consider(delta, build);
+        }
+        let (u, v) = (nurses[a].route[i], nurses[b].route[j]);
+        let delta = self.splice_delta(a, i, i + 1, &[v]) + self.splice_delta(b, j, j + 1, &[u]);
+        self.consider(delta, || vec![(a, spliced(&nurses[a].route, i, i + 1, &[v])), (b, spliced(&nurses[b].route, j, j + 1, &[u]))])
+    }
+
+    /// Reverse the patients between `u` and `v` and one of them, so they follow each other
+    fn two_opt_pair(&mut self, a: usize, i: usize, j: usize) -> bool {
+        let nurses = self.nurses;
+        let (start, end) = if i < j { (i + 1, j + 1) } else { (j, i) };
+        if end - start < 2 {
+            return false;
+        }
+        let reversed: Vec<i32> = nurses[a].route[start..end].iter().rev().copied().collect();
+        let delta = self.splice_delta(a, start, end, &reversed);
+        self.consider(delta, || vec![(a, spliced(&nurses[a].route, start, end, &reversed))])
+    }
+
+    /// Continue the route of `u` with `v` and the rest of its route, and the route of `v` with what
+    /// came after `u`
+    fn two_opt_star_pair(&mut self, a: usize, i: usize, b: usize, j: usize) -> bool {
+        let nurses = self.nurses;
+        let (len_a, len_b) = (nurses[a].route.len(), nurses[b].route.len());
+        let (tail_a, tail_b) = (&nurses[a].route[i + 1..], &nurses[b].route[j..]);
+        let delta = self.splice_delta(a, i + 1, len_a, tail_b) + self.splice_delta(b, j, len_b, tail_a);
+        self.consider(delta, || vec![(a, spliced(&nurses[a].route, i + 1, len_a, tail_b)), (b, spliced(&nurses[b].route, j, len_b, tail_a))])
+    }
+
+    /// Move `length` patients starting with `u` right before or right after `v`
+    fn or_opt_pair(&mut self, a: usize, i: usize, j: usize, length: usize) -> bool {
+        if i + length > self.nurses[a].route.len() || (i..i + length).contains(&j) {
+            return false;
+        }
+        let j = if j > i { j - length } else { j };
+        self.move_segment_to(a, i, length, j) || self.move_segment_to(a, i, length, j + 1)
+    }
+
+    /// Exchange the patients after `u` with the patients starting with `v`
+    fn cross_exchange_pair(&mut self, a: usize, i: usize, b: usize, j: usize) -> bool {
+        i + 1 < self.nurses[a].route.len() && self.exchange_segments(a, i + 1, b, j)
+    }
+}
+
+/// `route[start..start + length]`, reversed or not
+fn segment(route: &[i32], start: usize, length: usize, reverse: bool) -> Vec<i32> {
+    let mut segment = route[start..start + length].to_vec();
+    if reverse {
+        segment.reverse();
+    }
+    segment
+}
+
+/// `route` with `route[start..end]` replaced by `replacement`
+fn spliced(route: &[i32], start: usize, end: usize, replacement: &[i32]) -> Vec<i32> {
+    let mut changed = Vec::with_capacity(route.len() - (end - start) + replacement.len());
+    changed.extend_from_slice(&route[..start]);
+    changed.extend_from_slice(replacement);
+    changed.extend_from_slice(&route[end..]);
+    changed
+}
@@ -2,15 +2,18 @@ use std::collections::HashSet;
 use crate::structs::config::Config;
 use crate::structs::nurse::{Individual};
 
-pub fn get_elitism_members(population: &Vec<Individual>, config: &Config) -> Vec<Individual> {
+pub fn get_elitism_members(population: &[Individual], config: &Config) -> Vec<Individual> {
     let mut members_hashset: HashSet<&Individual> = HashSet::new();
+    // Keep the members in population order, the hash set order changes between runs
+    let mut members: Vec<Individual> = Vec::new();
     for individual in population.iter().rev() {
         if !members_hashset.contains(individual) {
             if members_hashset.len() >= config.n_elitism as usize {
                 break;
             }
             members_hashset.insert(individual);
+            members.push(individual.clone());
         }
     }
-    members_hashset.into_iter().cloned().collect::<Vec<Individual>>()
+    members
 }
\ No newline at end of file
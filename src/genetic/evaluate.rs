@@ -1,17 +1,20 @@
-use crate::structs::config::Config;
+#[cfg(test)]
+use ordered_float::OrderedFloat;
+
+use crate::structs::config::{Config, PenaltyModel};
 use crate::structs::io::{Info, Patient};
 use crate::structs::nurse::{Individual, Nurse};
 
 pub fn fitness_population(
-    population: &mut Vec<Individual>,
+    population: &mut [Individual],
     info: &Info,
     config: &Config,
 ) {
-    fitness_no_hashmap(population, &info, &config);
+    fitness_no_hashmap(population, info, config);
 }
 
 fn fitness_no_hashmap(
-    population: &mut Vec<Individual>,
+    population: &mut [Individual],
     info: &Info,
     config: &Config,
 ) {
@@ -34,6 +37,27 @@ fn fitness_no_hashmap(
 }
 
 pub fn fitness_nurse(nurse: &Nurse, info: &Info, config: &Config) -> (f32, bool) {
+    match config.penalty_model {
+        PenaltyModel::Legacy => legacy_fitness_nurse(nurse, info, config),
+        PenaltyModel::TimeWarp => {
+            let segment = Segment::of_route(&nurse.route, info);
+            (penalized_cost(&segment, info, config), segment.is_feasible(info))
+        }
+    }
+}
+
+/// Travel time of a whole route plus the `TimeWarp` penalties for lateness and excess demand
+pub fn penalized_cost(route: &Segment, info: &Info, config: &Config) -> f32 {
+    route.distance + penalties(route, info, config)
+}
+
+/// The `TimeWarp` penalties for lateness and excess demand of a sequence
+pub fn penalties(route: &Segment, info: &Info, config: &Config) -> f32 {
+    let excess_load = route.load.saturating_sub(info.capacity_nurse) as f32;
+    config.time_warp_penalty * route.time_warp + config.load_penalty * excess_load
+}
+
+fn legacy_fitness_nurse(nurse: &Nurse, info: &Info, config: &Config) -> (f32, bool) {
     let mut is_legal = true;
     if nurse.route.is_empty() {
         return (0.0, is_legal)
@@ -144,7 +168,7 @@ pub fn is_feasible_fitness_nurse(nurse: &Nurse, info: &Info) -> bool {
     if curr_demand > info.capacity_nurse {
         return false;
     }
-    time_used += info.travel_times[0][prev_p_idx] as f32;
+    time_used += info.travel_times[0][prev_p_idx];
 
     if time_used > info.depot.return_time as f32 {
         return false;
@@ -153,14 +177,112 @@ pub fn is_feasible_fitness_nurse(nurse: &Nurse, info: &Info) -> bool {
     true
 }
 
-pub fn get_best_fitness_population(population: &Vec<Individual>) -> f32 {
+/// Summary of a sequence of visits that can be concatenated with another in constant time
+/// (the segment concatenation of Vidal et al. 2013). Nodes are travel time matrix indices, so
+/// patient `p` is node `p + 1` and the depot is node 0.
+///
+/// Care may only start inside `[start_time, end_time - care_time]`. Starting care too late is
+/// counted as time warp: the schedule travels back in time to be on time, and the amount is
+/// summed up. A segment without time warp and within the capacity is feasible.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Segment {
+    pub first: usize,
+    pub last: usize,
+    /// Travel, waiting and care time of the sequence
+    pub duration: f32,
+    /// Earliest start of the first visit without waiting inside the sequence
+    pub earliest: f32,
+    /// Latest start of the first visit without adding time warp
+    pub latest: f32,
+    pub time_warp: f32,
+    pub load: u32,
+    pub distance: f32,
+}
+
+impl Segment {
+    /// Leaving from or returning to the depot
+    pub fn depot(info: &Info) -> Self {
+        Segment {
+            first: 0,
+            last: 0,
+            duration: 0.,
+            earliest: 0.,
+            latest: info.depot.return_time as f32,
+            time_warp: 0.,
+            load: 0,
+            distance: 0.,
+        }
+    }
+
+    /// A single visit to the zero-indexed `patient`
+    pub fn patient(patient: i32, info: &Info) -> Self {
+        let p = &info.patients[patient as usize];
+        let latest_start = p.end_time as f32 - p.care_time as f32;
+        let start = p.start_time as f32;
+        Segment {
+            first: patient as usize + 1,
+            last: patient as usize + 1,
+            duration: p.care_time as f32,
+            earliest: start,
+            latest: latest_start.max(start),
+            time_warp: (start - latest_start).max(0.),
+            load: p.demand,
+            distance: 0.,
+        }
+    }
+
+    /// The visits of `patients` in order, or `None` for no patients
+    pub fn of_patients(patients: &[i32], info: &Info) -> Option<Self> {
+        let (first, rest) = patients.split_first()?;
+        Some(rest.iter().fold(Segment::patient(*first, info), |segment, p| segment.concat(&Segment::patient(*p, info), info)))
+    }
+
+    /// A whole route from and back to the depot
+    pub fn of_route(route: &[i32], info: &Info) -> Self {
+        let depot = Segment::depot(info);
+        match Segment::of_patients(route, info) {
+            Some(patients) => depot.concat(&patients, info).concat(&depot, info),
+            None => depot.concat(&depot, info),
+        }
+    }
+
+    /// This sequence followed by `other`
+    pub fn concat(&self, other: &Segment, info: &Info) -> Self {
+        let travel = travel_time(self.last, other.first, info);
+        let delta = self.duration - self.time_warp + travel;
+        let wait = (other.earliest - delta - self.latest).max(0.);
+        let warp = (self.earliest + delta - other.latest).max(0.);
+        Segment {
+            first: self.first,
+            last: other.last,
+            duration: self.duration + other.duration + travel + wait,
+            earliest: (other.earliest - delta).max(self.earliest) - wait,
+            latest: (other.latest - delta).min(self.latest) + warp,
+            time_warp: self.time_warp + other.time_warp + warp,
+            load: self.load + other.load,
+            distance: self.distance + other.distance + travel,
+        }
+    }
+
+    pub fn is_feasible(&self, info: &Info) -> bool {
+        self.time_warp <= 0. && self.load <= info.capacity_nurse
+    }
+}
+
+/// Travel time between matrix nodes. The way back to the depot is read as `travel_times[0][from]`
+/// like in `fitness_nurse`.
+pub fn travel_time(from: usize, to: usize, info: &Info) -> f32 {
+    if to == 0 { info.travel_times[0][from] } else { info.travel_times[from][to] }
+}
+
+pub fn get_best_fitness_population(population: &[Individual]) -> f32 {
     let best_individual = population.iter()
         .min_by(|a, b| a.fitness.partial_cmp(&b.fitness).unwrap());
     best_individual.unwrap().fitness
 }
 
 #[cfg(test)]
-pub fn get_best_solution_population(population: &Vec<Individual>) -> Vec<Vec<i32>> {
+pub fn get_best_solution_population(population: &[Individual]) -> Vec<Vec<i32>> {
     let best_individual = population
         .iter()
         .min_by_key(|i| OrderedFloat(i.fitness))
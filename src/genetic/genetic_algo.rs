@@ -1,86 +1,221 @@
 use std::time::Instant;
 
+use crate::genetic::education::educate_population;
 use crate::genetic::evaluate::{fitness_population, get_best_fitness_population};
+use crate::genetic::granular::Granular;
 use crate::genetic::initialize_population::init_population;
 use crate::genetic::mutation::mutate_population;
+use crate::genetic::operators::Operators;
+use crate::genetic::penalty::PenaltyManager;
+use crate::genetic::population::Subpopulations;
+use crate::genetic::random::{new_rng, GaRng};
 use crate::genetic::parent_selection::parent_selection;
 use crate::genetic::crossover::population_crossover;
 use crate::genetic::elitism::get_elitism_members;
-use crate::genetic::island::start_islands;
 use crate::genetic::scramble::scramble_population;
 use crate::genetic::survivor_selection::survivor_selection;
-use crate::structs::io;
+use crate::structs::io::Info;
 use crate::structs::config::Config;
-use crate::util::save_individual::save_individual;
+use crate::structs::nurse::Individual;
+use crate::structs::stats::{GenerationStats, RunStats};
+
+/// Called every time a new best feasible individual is found
+pub type ImprovementFN = dyn Fn(&Individual) + Send + Sync;
+
+/// State of one population between generations. Used both by the single
+/// population run and by every island.
+pub(crate) struct Evolution<'a> {
+    info: &'a Info,
+    /// Own copy, as adaptive penalties change the weights per population
+    pub(crate) config: Config,
+    penalties: PenaltyManager,
+    operators: &'a Operators,
+    /// Granular neighbourhoods for education, with `granular_neighbours`
+    granular: Option<Granular>,
+    /// With `subpopulations`, the feasible and the infeasible subpopulation together
+    pub(crate) population: Vec<Individual>,
+    pub(crate) rng: GaRng,
+    stagnation_counter: i32,
+    best_fitness: f32,
+    pub(crate) best_individual: Option<Individual>,
+    pub(crate) generations: usize,
+    pub(crate) restarts: usize,
+}
 
-pub(crate) fn init(config: Config) {
-    if config.use_islands {
-        start_islands(config);
-    } else {
-        start(config)
+impl<'a> Evolution<'a> {
+    pub(crate) fn new(info: &'a Info, config: &'a Config, operators: &'a Operators, mut rng: GaRng) -> Self {
+        let mut population = init_population(info, config, &mut rng);
+
+        fitness_population(&mut population, info, config);
+
+        Evolution {
+            info,
+            config: config.clone(),
+            penalties: PenaltyManager::default(),
+            operators,
+            granular: (config.granular_neighbours > 0).then(|| Granular::new(info, config)),
+            population,
+            rng,
+            stagnation_counter: 0,
+            best_fitness: f32::INFINITY,
+            best_individual: None,
+            generations: 0,
+            restarts: 0,
+        }
     }
-}
 
-pub(crate) fn start(config: Config) {
-    let info = io::read_from_json(&config).unwrap();
+    /// Sort the population with the best individual last
+    pub(crate) fn sort(&mut self) {
+        self.population.sort_by(|p1, p2| p2.fitness.total_cmp(&p1.fitness));
+    }
 
-    let mut population = init_population(&info, &config);
+    /// Print and return statistics. Population must be sorted
+    pub(crate) fn log(&self, island: usize, i: i32, start: &Instant) -> GenerationStats {
+        let fitnesses: Vec<f32> = self.population.iter().map(|x| x.fitness).collect::<Vec<f32>>();
+        let last_fitnesses = &fitnesses[fitnesses.len().saturating_sub(5)..];
+        let avg_fitness = fitnesses.iter().sum::<f32>() / fitnesses.len() as f32;
+        println!("nGenerations: {} Best fitnesses: {:?} Avg Fitness: {} Execution_time {:?}", i, last_fitnesses, &avg_fitness, &start.elapsed());
+        GenerationStats {
+            island,
+            generation: i,
+            best_fitness: *fitnesses.last().unwrap(),
+            avg_fitness,
+            elapsed: start.elapsed(),
+        }
+    }
 
-    fitness_population(&mut population, &info, &config);
+    /// Run one generation. Population must be sorted
+    pub(crate) fn generation(&mut self, on_improvement: Option<&ImprovementFN>) {
+        let info = self.info;
+        let operators = self.operators;
 
-    let mut stagnation_counter: i32 = 0;
-    let mut best_fitness: f32 = f32::INFINITY;
-    let mut global_best_fitness: f32 = f32::INFINITY;
+        // Stagnation
+        let curr_fitness = self.population.last().unwrap().fitness;
+        if self.best_fitness > curr_fitness {
+            self.stagnation_counter = 0;
+            self.best_fitness = curr_fitness;
+            self.update_best_individual(on_improvement);
+        } else if self.stagnation_counter > (self.config.n_stagnations) {
+            self.stagnation_counter = 0;
+            self.restarts += 1;
+            scramble_population(&mut self.population, &mut self.rng, info, &self.config, operators.restart.as_ref());
+            fitness_population(&mut self.population, info, &self.config);
+            self.best_fitness = get_best_fitness_population(&self.population);
+        } else {
+            self.stagnation_counter += 1;
+        }
+        let config = &self.config;
+        let population = &mut self.population;
+        let rng = &mut self.rng;
+
+        // Subpopulations keep the best individuals anyway
+        let mut elitism_members = Vec::new();
+        if !config.subpopulations {
+            elitism_members = get_elitism_members(population, config);
+            population.drain(0..config.n_elitism as usize);
+        }
 
-    let start = Instant::now();
+        let parent_indices: Vec<usize> = parent_selection(population, rng, config, operators.parent_selection.as_ref());
 
-    for i in 0..config.n_generations {
-        population.sort_by(|p1, p2| p2.fitness.total_cmp(&p1.fitness));
+        let mut children_population = population_crossover(population, &parent_indices, rng, info, config, operators.crossover.as_ref());
 
-        if i % config.log_frequency == 0 {
-            let fitnesses: Vec<f32> = population.iter().map(|x| x.fitness).collect::<Vec<f32>>();
-            let last_fitnesses = &fitnesses[fitnesses.len()-5..];
-            let avg_fitness = fitnesses.iter().sum::<f32>() / fitnesses.len() as f32;
-            println!("nGenerations: {} Best fitnesses: {:?} Avg Fitness: {} Execution_time {:?}", i, last_fitnesses, &avg_fitness, &start.elapsed());
+        mutate_population(&mut children_population, rng, config, info, &operators.mutations);
+        if config.education {
+            educate_population(&mut children_population, info, config, self.granular.as_ref());
         }
 
-        // Stagnation
-        let curr_fitness = population.last().unwrap().fitness;
-        if best_fitness > curr_fitness {
-            stagnation_counter = 0;
-            best_fitness = curr_fitness;
-            // Only save good solutions that are within the benchmark range
-            if curr_fitness < global_best_fitness && curr_fitness < info.benchmark * 1.05 {
-                global_best_fitness = curr_fitness;
-                save_individual(&population, &config);
-            }
-        } else if stagnation_counter > (config.n_stagnations) {
-            stagnation_counter = 0;
-            scramble_population(&mut population, &info, &config);
-            fitness_population(&mut population, &info, &config);
-            best_fitness = get_best_fitness_population(&population);
+        fitness_population(&mut children_population, info, config);
+        if config.adaptive_penalties {
+            self.penalties.record(&children_population, info);
+        }
+
+        if config.subpopulations {
+            let mut subpopulations = Subpopulations::new(population.drain(..).chain(children_population));
+            subpopulations.select_survivors(config);
+            *population = subpopulations.into_population();
         } else {
-            stagnation_counter += 1;
+            survivor_selection(population, &parent_indices, &children_population, rng, config, operators.survivor_selection.as_ref());
         }
-        let mut elitism_members = get_elitism_members(&population, &config);
-        population.drain(0..config.n_elitism as usize);
 
-        let parent_indices: Vec<usize> = parent_selection(&mut population, &config);
+        population.append(&mut elitism_members);
+        self.generations += 1;
+
+        if self.config.adaptive_penalties
+            && self.generations.is_multiple_of(self.config.penalty_adjust_frequency.max(1) as usize)
+            && self.penalties.adjust(&mut self.config)
+        {
+            // Fitnesses under the old weights can not be compared with new ones
+            fitness_population(&mut self.population, info, &self.config);
+            self.best_fitness = get_best_fitness_population(&self.population);
+        }
+    }
 
-        let mut children_population = population_crossover(&mut population, &parent_indices, &info, &config);
+    /// Evaluate an individual coming from another population, which may use other penalty weights
+    pub(crate) fn evaluate(&self, individual: &mut Individual) {
+        fitness_population(std::slice::from_mut(individual), self.info, &self.config);
+    }
 
-        mutate_population(&mut children_population, &config, &info);
+    fn update_best_individual(&mut self, on_improvement: Option<&ImprovementFN>) {
+        // Population is sorted, so the last individual is the best one
+        let candidate = match self.population
+            .iter()
+            .filter(|i| i.feasible)
+            .min_by(|a, b| a.fitness.total_cmp(&b.fitness)) {
+            Some(best_feasible) => best_feasible,
+            None => self.population.last().unwrap(),
+        };
+
+        let improved = match &self.best_individual {
+            Some(best) => candidate.is_better_than(best),
+            None => true,
+        };
+        if improved {
+            self.best_individual = Some(candidate.clone());
+            if let (true, Some(on_improvement)) = (candidate.feasible, on_improvement) {
+                on_improvement(candidate);
+            }
+        }
+    }
 
-        fitness_population(&mut children_population, &info, &config);
+    /// The best individual found, preferring feasible ones
+    pub(crate) fn into_best_individual(mut self) -> Individual {
+        self.sort();
+        self.update_best_individual(None);
+        self.best_individual.unwrap()
+    }
+}
 
-        survivor_selection(&mut population, &parent_indices, &mut children_population, &config);
+pub(crate) fn start(
+    info: &Info,
+    config: &Config,
+    operators: &Operators,
+    on_improvement: Option<&ImprovementFN>,
+) -> (Individual, RunStats) {
+    let mut evolution = Evolution::new(info, config, operators, new_rng(config.seed));
+    let mut history = Vec::new();
 
-        population.append(&mut elitism_members);
+    let start = Instant::now();
 
-        if config.run_time != -1 {
-            if start.elapsed().as_secs() > config.run_time as u64 {
-                break;
-            }
+    for i in 0..config.n_generations {
+        evolution.sort();
+
+        if i % config.log_frequency == 0 {
+            history.push(evolution.log(0, i, &start));
+        }
+
+        evolution.generation(on_improvement);
+
+        if config.run_time != -1 && start.elapsed().as_secs() > config.run_time as u64 {
+            break;
         }
     }
-}
\ No newline at end of file
+
+    let stats = RunStats {
+        generations: evolution.generations,
+        restarts: evolution.restarts,
+        islands: 1,
+        elapsed: start.elapsed(),
+        history,
+    };
+    (evolution.into_best_individual(), stats)
+}
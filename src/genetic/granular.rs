@@ -0,0 +1,47 @@
+use crate::structs::config::Config;
+use crate::structs::io::Info;
+
+/// The patients local search may connect each patient to (granular neighbourhoods, Toth & Vigo
+/// 2003). Candidates come from `travel_times_sorted`, so at most `NEIGHBOUR_LIST_SIZE` per patient.
+pub struct Granular {
+    neighbours: Vec<Vec<i32>>,
+}
+
+impl Granular {
+    /// The `granular_neighbours` most correlated patients of every patient. Without a
+    /// `granular_time_window_weight` those are the nearest ones, otherwise the waiting and the
+    /// lateness from going from one to the other are added to the travel time (Vidal et al. 2013)
+    pub fn new(info: &Info, config: &Config) -> Self {
+        let neighbours = (0..info.patients.len())
+            .map(|u| {
+                let mut candidates: Vec<usize> = info.travel_times_sorted[u + 1].iter()
+                    .filter(|node| **node != 0)
+                    .map(|node| node - 1)
+                    .collect();
+                if config.granular_time_window_weight > 0. {
+                    let weight = config.granular_time_window_weight;
+                    candidates.sort_by(|a, b| correlation(u, *a, weight, info).total_cmp(&correlation(u, *b, weight, info)));
+                }
+                candidates.truncate(config.granular_neighbours);
+                candidates.into_iter().map(|v| v as i32).collect()
+            })
+            .collect();
+        Granular { neighbours }
+    }
+
+    pub fn neighbours(&self, patient: i32) -> &[i32] {
+        &self.neighbours[patient as usize]
+    }
+}
+
+/// Travel time from `u` to `v` plus `weight` times the least waiting and lateness at `v` when
+/// care at `v` follows care at `u`
+fn correlation(u: usize, v: usize, weight: f32, info: &Info) -> f32 {
+    let (from, to) = (&info.patients[u], &info.patients[v]);
+    let travel = info.travel_times[u + 1][v + 1];
+    let latest_from = from.end_time as f32 - from.care_time as f32;
+    let latest_to = to.end_time as f32 - to.care_time as f32;
+    let wait = (to.start_time as f32 - latest_from - from.care_time as f32 - travel).max(0.);
+    let lateness = (from.start_time as f32 + from.care_time as f32 + travel - latest_to).max(0.);
+    travel + weight * (wait + lateness)
+}
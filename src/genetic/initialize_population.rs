@@ -2,31 +2,31 @@ use std::fs;
 use crate::structs::io::{Info, Patient};
 use crate::structs::nurse::{Individual, Nurse};
 use crate::genetic::evaluate::is_feasible_fitness_nurse;
+use crate::genetic::random::GaRng;
 use crate::structs::config::{Config, InitialPopType};
+use crate::util::save_individual::{fitness_from_file_name, individual_folder, read_individual};
 
-use rand::{rng, Rng};
-use serde_json::from_str;
+use rand::Rng;
 
-type InitIndividualFN = fn(&Info, &Config) -> Vec<Individual>;
+type InitIndividualFN = fn(&Info, &Config, &mut GaRng) -> Vec<Individual>;
 
-pub fn init_population(info: &Info, config: &Config) -> Vec<Individual> {
+pub fn init_population(info: &Info, config: &Config, rng: &mut GaRng) -> Vec<Individual> {
     let init_fn: InitIndividualFN = match config.init_population_fn {
         InitialPopType::Feasible => feasible_pop,
         InitialPopType::File => get_population_from_file,
     };
-    init_fn(&info, &config)
+    init_fn(info, config, rng)
 }
 
-fn feasible_pop(info: &Info, config: &Config) -> Vec<Individual> {
+fn feasible_pop(info: &Info, config: &Config, rng: &mut GaRng) -> Vec<Individual> {
     let mut pop = Vec::new();
     for _ in 0..config.population_size as  usize {
-        pop.push(feasible_init_individual(&info, &config));
+        pop.push(feasible_init_individual(info, config, rng));
     }
     pop
 }
 
-pub(crate) fn feasible_init_individual(info: &Info, _config: &Config) -> Individual {
-    let mut rng = rand::rng();
+pub(crate) fn feasible_init_individual(info: &Info, _config: &Config, rng: &mut GaRng) -> Individual {
     let patients = info.patients
         .iter()
         .clone()
@@ -42,7 +42,7 @@ pub(crate) fn feasible_init_individual(info: &Info, _config: &Config) -> Individ
             nurses[nurse_idx].route.insert(route_idx, *patient_idx as i32);
 
             n += 1;
-            if is_feasible_fitness_nurse(&nurses[nurse_idx], &info) || n > 580 {
+            if is_feasible_fitness_nurse(&nurses[nurse_idx], info) || n > 580 {
                 break 'outer;
             }
             nurses[nurse_idx].route.remove(route_idx);
@@ -52,17 +52,21 @@ pub(crate) fn feasible_init_individual(info: &Info, _config: &Config) -> Individ
     Individual{nurses, fitness: -9999., feasible: false}
 }
 
-fn get_population_from_file(info: &Info, config: &Config) -> Vec<Individual> {
-    let folder_path = "./individuals/".to_owned() + &config.file_name.to_string() + "/";
+fn get_population_from_file(info: &Info, config: &Config, rng: &mut GaRng) -> Vec<Individual> {
+    let folder_path = individual_folder(config);
 
     let mut population: Vec<Vec<Vec<i32>>> = Vec::new();
 
-    for entry in fs::read_dir(&folder_path).unwrap() {
-        let entry = entry.unwrap();
-        let filename = entry.file_name();
-        let fp = format!("{}/{}", folder_path, &filename.to_str().unwrap());
-        let file_content = fs::read_to_string(&fp).unwrap();
-        population.push(from_str(&file_content).unwrap());
+    // Sorted, so seeded runs do not depend on the directory order
+    let mut entries: Vec<fs::DirEntry> = fs::read_dir(&folder_path).unwrap().map(|entry| entry.unwrap()).collect();
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        // Only files named after their fitness are saved individuals
+        if entry.file_name().to_str().and_then(fitness_from_file_name).is_none() {
+            continue;
+        }
+        population.push(read_individual(&entry.path()).unwrap());
     }
 
     let mut new_pop: Vec<Individual> = Vec::new();
@@ -81,12 +85,12 @@ fn get_population_from_file(info: &Info, config: &Config) -> Vec<Individual> {
 
     if new_pop.len() < config.population_size as usize {
         for _ in 0..config.population_size as usize - new_pop.len() {
-            let new_member = new_pop[rng().random_range(0..new_pop.len())].clone();
+            let new_member = new_pop[rng.random_range(0..new_pop.len())].clone();
             new_pop.push(new_member);
         }
     } else if new_pop.len() > config.population_size as usize {
         for _ in 0..new_pop.len()-config.population_size as usize {
-            new_pop.remove(rng().random_range(0..new_pop.len()));
+            new_pop.remove(rng.random_range(0..new_pop.len()));
         }
     }
 
@@ -0,0 +1,55 @@
+use rand::Rng;
+
+use crate::genetic::education::improve_route;
+use crate::genetic::evaluate::fitness_population;
+use crate::genetic::random::GaRng;
+use crate::structs::config::{Config, Neighbourhood};
+use crate::structs::io::Info;
+use crate::structs::nurse::{Individual, Nurse};
+
+/// Apply the first improving 2-opt or Or-opt move on every route until none is left, then
+/// evaluate the individual again
+pub fn polish(individual: &mut Individual, info: &Info, config: &Config) {
+    for nurse in individual.nurses.iter_mut() {
+        polish_route(&mut nurse.route, info, config);
+    }
+    fitness_population(std::slice::from_mut(individual), info, config);
+}
+
+pub fn polish_route(route: &mut [i32], info: &Info, config: &Config) {
+    while two_opt(route, info, config) || or_opt(route, info, config) {}
+}
+
+/// Reverse the part of the route that lowers the cost, if any. Returns whether the route changed
+pub fn two_opt(route: &mut [i32], info: &Info, config: &Config) -> bool {
+    improve_route(route, &[Neighbourhood::TwoOpt], info, config)
+}
+
+/// Move a chain of one to three patients elsewhere in the route, if that lowers the cost.
+/// Returns whether the route changed
+pub fn or_opt(route: &mut [i32], info: &Info, config: &Config) -> bool {
+    improve_route(route, &[Neighbourhood::Relocate, Neighbourhood::OrOpt], info, config)
+}
+
+/// Route of a random nurse with at least two patients, if there is one
+fn random_route<'a>(nurses: &'a mut [Nurse], rng: &mut GaRng) -> Option<&'a mut [i32]> {
+    let candidates: Vec<usize> = (0..nurses.len()).filter(|i| nurses[*i].route.len() > 1).collect();
+    if candidates.is_empty() {
+        return None;
+    }
+    Some(&mut nurses[candidates[rng.random_range(0..candidates.len())]].route)
+}
+
+/// 2-opt on a random route until no reversal improves it
+pub fn two_opt_mutation(nurses: &mut [Nurse], rng: &mut GaRng, info: &Info, config: &Config) {
+    if let Some(route) = random_route(nurses, rng) {
+        while two_opt(route, info, config) {}
+    }
+}
+
+/// Or-opt on a random route until no move of a chain improves it
+pub fn or_opt_mutation(nurses: &mut [Nurse], rng: &mut GaRng, info: &Info, config: &Config) {
+    if let Some(route) = random_route(nurses, rng) {
+        while or_opt(route, info, config) {}
+    }
+}
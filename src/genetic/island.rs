@@ -1,143 +1,140 @@
-use std::sync::{Arc, Mutex};
 use std::thread;
-use std::thread::available_parallelism;
 use std::time::Instant;
 
 use rand::prelude::*;
 use rand::distr::weighted::WeightedIndex;
-use rand::rng;
-use crate::genetic::crossover::population_crossover;
-use crate::genetic::elitism::get_elitism_members;
-use crate::genetic::evaluate::{fitness_population, get_best_fitness_population};
-use crate::genetic::initialize_population::init_population;
-use crate::genetic::mutation::mutate_population;
-use crate::genetic::parent_selection::parent_selection;
-use crate::genetic::scramble::scramble_population;
-use crate::genetic::survivor_selection::survivor_selection;
+use crate::genetic::genetic_algo::{Evolution, ImprovementFN};
+use crate::genetic::operators::Operators;
+use crate::genetic::random::{child_rng, new_rng};
 use crate::structs::config::Config;
-use crate::structs::io;
+use crate::structs::io::Info;
 use crate::structs::nurse::Individual;
-use crate::util::save_individual::save_individual;
-
-pub(crate) fn start_islands(config: Config) {
-    let num_islands: usize = available_parallelism().unwrap().get()-1;
-    let shared_individuals = Arc::new(Mutex::new(Vec::new()));
-
+use crate::structs::stats::{GenerationStats, RunStats};
+
+/// Run one population per thread. Every `island_share_frequency` generations all islands
+/// stop and each sends one individual to the next island in a ring, so the migrations are
+/// the same from run to run.
+pub(crate) fn start_islands(
+    info: &Info,
+    config: &Config,
+    operators: &Operators,
+    num_islands: usize,
+    on_improvement: Option<&ImprovementFN>,
+) -> (Individual, RunStats) {
     println!("Creating {} islands!", num_islands);
 
-    let mut handles = vec![];
-
-    for _ in 0..num_islands {
-        let shared_clone = Arc::clone(&shared_individuals);
-        let config_clone = config.clone(); // Ensure Config is Clone
-
-        let handle = thread::spawn(move || {
-            islands(config_clone, shared_clone);
+    let start = Instant::now();
+    let mut rng = new_rng(config.seed);
+    let island_rngs: Vec<_> = (0..num_islands).map(|_| child_rng(&mut rng)).collect();
+
+    let mut evolutions: Vec<Evolution> = thread::scope(|scope| {
+        let handles: Vec<_> = island_rngs
+            .into_iter()
+            .map(|island_rng| scope.spawn(move || Evolution::new(info, config, operators, island_rng)))
+            .collect();
+        handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+    });
+
+    let mut history = Vec::new();
+    let mut generation = 0;
+    let share_frequency = config.island_share_frequency.max(1);
+
+    while generation < config.n_generations && !time_is_up(config, &start) {
+        let epoch_end = (generation + share_frequency).min(config.n_generations);
+
+        let epoch_history: Vec<Vec<GenerationStats>> = thread::scope(|scope| {
+            let handles: Vec<_> = evolutions
+                .iter_mut()
+                .enumerate()
+                .map(|(island, evolution)| {
+                    scope.spawn(move || run_epoch(island, evolution, generation..epoch_end, config, &start, on_improvement))
+                })
+                .collect();
+            handles.into_iter().map(|handle| handle.join().unwrap()).collect()
         });
+        history.extend(epoch_history.into_iter().flatten());
 
-        handles.push(handle);
-    }
-
-    for handle in handles {
-        handle.join().unwrap();
+        migrate(&mut evolutions);
+        generation = epoch_end;
     }
-}
-
-pub(crate) fn islands(config: Config, shared_individuals: Arc<Mutex<Vec<Individual>>>) {
-    let info = io::read_from_json(&config).unwrap();
-    let mut rng = rng();
-
-    let mut population = init_population(&info, &config);
-
-    fitness_population(&mut population, &info, &config);
 
-    let mut stagnation_counter: i32 = 0;
-    let mut best_fitness: f32 = f32::INFINITY;
-    let mut global_best_fitness: f32 = f32::INFINITY;
-
-    let start = Instant::now();
-
-    for i in 0..config.n_generations {
-        if config.run_time != -1 {
-            if start.elapsed().as_secs() > config.run_time as u64 {
-                break;
-            }
+    let mut stats = RunStats { islands: num_islands, history, ..RunStats::default() };
+    let mut best_individual: Option<Individual> = None;
+    for evolution in evolutions {
+        stats.generations += evolution.generations;
+        stats.restarts += evolution.restarts;
+
+        let island_best = evolution.into_best_individual();
+        let better = match &best_individual {
+            Some(best) => island_best.is_better_than(best),
+            None => true,
+        };
+        if better {
+            best_individual = Some(island_best);
         }
-        population.sort_by(|p1, p2| p2.fitness.total_cmp(&p1.fitness));
+    }
+    stats.elapsed = start.elapsed();
 
+    (best_individual.unwrap(), stats)
+}
 
-        if i % config.island_share_frequency == 0 {
-            // Compute inverse fitness values (lower fitness = higher probability)
-            let mut fitness_weights: Vec<f32> = population
-                .iter()
-                .map(|ind| 1.0 / (ind.fitness)) // Avoid division by zero
-                .collect();
+fn run_epoch(
+    island: usize,
+    evolution: &mut Evolution,
+    generations: std::ops::Range<i32>,
+    config: &Config,
+    start: &Instant,
+    on_improvement: Option<&ImprovementFN>,
+) -> Vec<GenerationStats> {
+    let mut history = Vec::new();
 
-            let sum: f32 = fitness_weights.iter().sum();
-            for fitness_idx in 0..fitness_weights.len() {
-                fitness_weights[fitness_idx] /= sum;
-            }
-
-            // Create weighted distribution
-            if let Ok(weighted_dist) = WeightedIndex::new(&fitness_weights) {
-                let selected_index = weighted_dist.sample(&mut rng);
-                let selected_individual = population[selected_index].clone();
-
-                let mut shared = shared_individuals.lock().unwrap();
-                if !shared.is_empty() {
-                    let migrant = shared.pop().unwrap();
-                    population.push(migrant);
-                }
-                shared.push(selected_individual);
-                population.remove(selected_index);
-            }
-        }
-        population.sort_by(|p1, p2| p2.fitness.total_cmp(&p1.fitness));
+    for i in generations {
+        evolution.sort();
 
         if i % config.log_frequency == 0 {
-            let fitnesses: Vec<f32> = population.iter().map(|x| x.fitness).collect::<Vec<f32>>();
-            let last_fitnesses = &fitnesses[fitnesses.len()-5..];
-            let avg_fitness = fitnesses.iter().sum::<f32>() / fitnesses.len() as f32;
-            println!("nGenerations: {} Best fitnesses: {:?} Avg Fitness: {} Execution_time {:?}", i, last_fitnesses, &avg_fitness, &start.elapsed());
-        }
-
-        // Stagnation
-        let curr_fitness = population.last().unwrap().fitness;
-        if best_fitness > curr_fitness {
-            stagnation_counter = 0;
-            best_fitness = curr_fitness;
-            // Only save good solutions that are within the benchmark range
-            if curr_fitness < global_best_fitness && curr_fitness < info.benchmark * 1.05 {
-                global_best_fitness = curr_fitness;
-                save_individual(&population, &config);
-            }
-        } else if stagnation_counter > (config.n_stagnations) {
-            stagnation_counter = 0;
-            scramble_population(&mut population, &info, &config);
-            fitness_population(&mut population, &info, &config);
-            best_fitness = get_best_fitness_population(&population);
-        } else {
-            stagnation_counter += 1;
+            history.push(evolution.log(island, i, start));
         }
-        let mut elitism_members = get_elitism_members(&population, &config);
-        population.drain(0..config.n_elitism as usize);
-
-        let parent_indices: Vec<usize> = parent_selection(&mut population, &config);
 
-        let mut children_population = population_crossover(&mut population, &parent_indices, &info, &config);
+        evolution.generation(on_improvement);
 
-        mutate_population(&mut children_population, &config, &info);
-
-        fitness_population(&mut children_population, &info, &config);
+        if time_is_up(config, start) {
+            break;
+        }
+    }
+    history
+}
 
-        survivor_selection(&mut population, &parent_indices, &mut children_population, &config);
+/// Each island sends an individual, picked with probability inverse to its fitness,
+/// to the next island
+fn migrate(evolutions: &mut [Evolution]) {
+    if evolutions.len() < 2 {
+        return;
+    }
 
-        population.append(&mut elitism_members);
+    let mut migrants = Vec::new();
+    for evolution in evolutions.iter_mut() {
+        // Compute inverse fitness values (lower fitness = higher probability)
+        let fitness_weights: Vec<f32> = evolution.population
+            .iter()
+            .map(|ind| 1.0 / (ind.fitness)) // Avoid division by zero
+            .collect();
+
+        // Create weighted distribution
+        let selected_index = match WeightedIndex::new(&fitness_weights) {
+            Ok(weighted_dist) => weighted_dist.sample(&mut evolution.rng),
+            Err(_) => evolution.rng.random_range(0..evolution.population.len()),
+        };
+        migrants.push(evolution.population.remove(selected_index));
+    }
 
-        if config.run_time != -1 {
-            if start.elapsed().as_secs() > config.run_time as u64 {
-                break;
-            }
-        }
+    migrants.rotate_right(1);
+    for (evolution, mut migrant) in evolutions.iter_mut().zip(migrants) {
+        evolution.evaluate(&mut migrant);
+        evolution.population.push(migrant);
+        evolution.sort();
     }
-}
\ No newline at end of file
+}
+
+fn time_is_up(config: &Config, start: &Instant) -> bool {
+    config.run_time != -1 && start.elapsed().as_secs() > config.run_time as u64
+}
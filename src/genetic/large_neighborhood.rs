@@ -1,11 +1,12 @@
 use rand::Rng;
-use rand::rngs::ThreadRng;
-use crate::genetic::evaluate::fitness_nurse;
+use crate::genetic::evaluate::Segment;
+use crate::genetic::route_cache::RouteCache;
+use crate::genetic::random::GaRng;
 use crate::structs::config::Config;
 use crate::structs::io::Info;
 use crate::structs::nurse::Nurse;
 
-pub fn destroy_and_repair(individual: &mut Vec<Nurse>, rng: &mut ThreadRng, info: &Info, config: &Config) {
+pub fn destroy_and_repair(individual: &mut [Nurse], rng: &mut GaRng, info: &Info, config: &Config) {
     // Should nurse with more than 6 patients
     let mut nurse_idx = 0;
     let mut found = false;
@@ -30,11 +31,14 @@ pub fn destroy_and_repair(individual: &mut Vec<Nurse>, rng: &mut ThreadRng, info
     let mut lowest_fitness = f32::INFINITY;
     let (mut best_nurse_i, mut best_patient_i) = (0,0);
 
-    for nurse_i in 0..individual.len() {
-        for patient_pos in 0..individual[nurse_i].route.len() {
-            individual[nurse_i].route.splice(patient_pos..patient_pos, patients_to_move.clone().into_iter());
-            let after_fitness_nurse = fitness_nurse(&individual[nurse_i], &info, &config).0;
-            individual[nurse_i].route.drain(patient_pos..patient_pos+patients_to_move.len());
+    let moved_segment = Segment::of_patients(&patients_to_move, info);
+
+    for (nurse_i, nurse) in individual.iter().enumerate() {
+        let cache = RouteCache::new(&nurse.route, info, config);
+        for patient_pos in 0..nurse.route.len() {
+            let after_fitness_nurse = cache.cost + cache.splice_segment_delta(
+                &nurse.route, patient_pos, patient_pos, &patients_to_move, moved_segment.as_ref(), info, config
+            );
             if after_fitness_nurse < lowest_fitness {
                 lowest_fitness = after_fitness_nurse;
                 best_nurse_i = nurse_i;
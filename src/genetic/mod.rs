@@ -2,10 +2,21 @@ pub mod genetic_algo;
 pub mod initialize_population;
 pub mod mutation;
 pub mod evaluate;
-mod crossover;
+pub(crate) mod crossover;
 pub mod parent_selection;
 pub mod survivor_selection;
 pub mod large_neighborhood;
-mod island;
+pub(crate) mod island;
 mod elitism;
-pub mod scramble;
\ No newline at end of file
+pub mod scramble;
+pub mod operators;
+pub mod random;
+pub mod route_cache;
+pub(crate) mod penalty;
+pub(crate) mod population;
+pub mod diversity;
+pub mod split;
+pub mod education;
+pub mod granular;
+pub mod intra_route;
+pub mod ruin_recreate;
@@ -1,56 +1,59 @@
 use std::iter::zip;
+use std::sync::Arc;
 use crate::structs::config::Config;
 use crate::structs::nurse::{Individual, Nurse};
-use crate::genetic::evaluate::fitness_nurse;
+use crate::genetic::evaluate::{fitness_nurse, Segment};
 use crate::genetic::parent_selection::linear_rank_probability;
 use crate::structs::io::Info;
 
 use rand::Rng;
 use rand::distr::weighted::WeightedIndex;
 use rand::distr::Distribution;
-use rand::prelude::ThreadRng;
+use rand::{RngCore, SeedableRng};
 
 use rayon::prelude::*;
-use crate::genetic::large_neighborhood::destroy_and_repair;
-
-pub fn mutate_population(population: &mut Vec<Individual>, config: &Config, info: &Info) {
+use crate::genetic::operators::Mutation;
+use crate::genetic::random::GaRng;
+use crate::genetic::route_cache::RouteCache;
+
+pub fn mutate_population(
+    population: &mut [Individual],
+    rng: &mut GaRng,
+    config: &Config,
+    info: &Info,
+    mutations: &[(Arc<dyn Mutation>, f32)],
+) {
+    // One seed per individual, so the result does not depend on how the work is split between threads
+    let seeds: Vec<u64> = population.iter().map(|_| rng.next_u64()).collect();
     if config.use_islands {
-        mutate_serial(population, &config, &info);
+        mutate_serial(population, &seeds, config, info, mutations);
     } else {
-        mutate_parallel(population, &config, &info);
+        mutate_parallel(population, &seeds, config, info, mutations);
     }
 }
 
-fn mutate_parallel(population: &mut Vec<Individual>, config: &Config, info: &Info) {
-    population.par_iter_mut().for_each(|individual| mutate_nurse(&mut individual.nurses, &info, &config));
+fn mutate_parallel(population: &mut [Individual], seeds: &[u64], config: &Config, info: &Info, mutations: &[(Arc<dyn Mutation>, f32)]) {
+    population.par_iter_mut().zip(seeds).for_each(|(individual, seed)| {
+        mutate_nurse(&mut individual.nurses, &mut GaRng::seed_from_u64(*seed), info, config, mutations)
+    });
 }
 
-fn mutate_serial(population: &mut Vec<Individual>, config: &Config, info: &Info) {
-    population.iter_mut().for_each(|individual| mutate_nurse(&mut individual.nurses, &info, &config));
+fn mutate_serial(population: &mut [Individual], seeds: &[u64], config: &Config, info: &Info, mutations: &[(Arc<dyn Mutation>, f32)]) {
+    population.iter_mut().zip(seeds).for_each(|(individual, seed)| {
+        mutate_nurse(&mut individual.nurses, &mut GaRng::seed_from_u64(*seed), info, config, mutations)
+    });
 }
 
-type MutationFN = fn(nurses: &mut Vec<Nurse>, rng: &mut ThreadRng, info: &Info, config: &Config);
-
-pub fn mutate_nurse(individual: &mut Vec<Nurse>, info: &Info, config: &Config) {
-    let mut rng: ThreadRng= rand::rng();
-
-    let mutations: [(MutationFN, f32); 5] = [
-        (heuristic_cluster_mutation, config.heuristic_cluster_mutation_rate),
-        (heuristic_swap_mutation, config.heuristic_swap_mutation_rate),
-        (heurisitc_random_cross_swap_mutation2, config.heuristic_random_swap_mutation_rate),
-        (destroy_and_repair, config.large_neighbourhood_mutation_rate),
-        (heuristic_insert_mutation, config.insert_mutation_rate),
-    ];
-
-    for mutation_pair in &mutations {
+pub fn mutate_nurse(individual: &mut [Nurse], rng: &mut GaRng, info: &Info, config: &Config, mutations: &[(Arc<dyn Mutation>, f32)]) {
+    for mutation_pair in mutations {
         if rng.random_range(0.0..1.0) < mutation_pair.1 {
-            mutation_pair.0(individual, &mut rng, &info, &config);
+            mutation_pair.0.mutate(individual, rng, info, config);
         }
     }
 }
 
 
-pub fn heuristic_cluster_mutation(nurses: &mut Vec<Nurse>, rng: &mut ThreadRng, info: &Info, config: &Config) {
+pub fn heuristic_cluster_mutation(nurses: &mut [Nurse], rng: &mut GaRng, info: &Info, config: &Config) {
     let mut nurse_idx;
     loop {
         nurse_idx = rng.random_range(0..nurses.len());
@@ -68,14 +71,16 @@ pub fn heuristic_cluster_mutation(nurses: &mut Vec<Nurse>, rng: &mut ThreadRng,
 
     let cluster: Vec<i32> = nurses[nurse_idx].route.drain(start_idx..end_idx).collect();
 
-    for (nurse_idx, nurse) in nurses.iter_mut().enumerate() {
-        let before_fitness = fitness_nurse(&nurse, &info, &config).0;
+    let cluster_segment = Segment::of_patients(&cluster, info);
+
+    for (nurse_idx, nurse) in nurses.iter().enumerate() {
+        let cache = RouteCache::new(&nurse.route, info, config);
         for route_idx in 0..nurse.route.len() {
-            nurse.route.splice(route_idx..route_idx, cluster.clone().into_iter());
-            let after_fitness = fitness_nurse(&nurse, &info, &config).0;
-            nurse.route.drain(route_idx..route_idx+cluster.len());
-            if after_fitness - before_fitness < lowest_fitness {
-                lowest_fitness = after_fitness - before_fitness;
+            let fitness_change = cache.splice_segment_delta(
+                &nurse.route, route_idx, route_idx, &cluster, cluster_segment.as_ref(), info, config
+            );
+            if fitness_change < lowest_fitness {
+                lowest_fitness = fitness_change;
                 best_nurse_idx = nurse_idx;
                 best_route_idx = route_idx;
             }
@@ -85,7 +90,7 @@ pub fn heuristic_cluster_mutation(nurses: &mut Vec<Nurse>, rng: &mut ThreadRng,
     nurses[best_nurse_idx].route.splice(best_route_idx..best_route_idx, cluster);
 }
 
-fn heuristic_swap_mutation(nurses: &mut Vec<Nurse>, rng: &mut ThreadRng, info: &Info, config: &Config) {
+pub fn heuristic_swap_mutation(nurses: &mut [Nurse], rng: &mut GaRng, info: &Info, config: &Config) {
     // Select a random nurse with at least 2 patients
     let mut nurse_idx;
     loop {
@@ -99,20 +104,17 @@ fn heuristic_swap_mutation(nurses: &mut Vec<Nurse>, rng: &mut ThreadRng, info: &
     let mut best_pos1 = 0;
     let mut best_pos2 = 0;
 
-    let before_fitness = fitness_nurse(&nurses[nurse_idx], &info, &config).0;
-
-    for i in 0..nurses[nurse_idx].route.len() {
-        for j in 0..nurses[nurse_idx].route.len() {
-            if i != j {
-                nurses[nurse_idx].route.swap(i,j);
-                let after_fitness = fitness_nurse(&nurses[nurse_idx], &info, &config).0;
-                nurses[nurse_idx].route.swap(j,i);
-
-                if after_fitness - before_fitness < lowest_fitness {
-                    lowest_fitness = after_fitness - before_fitness;
-                    best_pos1 = i;
-                    best_pos2 = j;
-                }
+    let route = &nurses[nurse_idx].route;
+    let cache = RouteCache::new(route, info, config);
+
+    // Swapping is symmetric, so each pair is only tried once
+    for i in 0..route.len() {
+        for j in i + 1..route.len() {
+            let fitness_change = cache.swap_delta(route, i, j, info, config);
+            if fitness_change < lowest_fitness {
+                lowest_fitness = fitness_change;
+                best_pos1 = i;
+                best_pos2 = j;
             }
         }
     }
@@ -126,7 +128,7 @@ struct SwapFitness {
     fitness: f32,
 }
 
-fn heurisitc_random_cross_swap_mutation2(nurses: &mut Vec<Nurse>, rng: &mut ThreadRng, info: &Info, config: &Config) {
+pub fn heurisitc_random_cross_swap_mutation2(nurses: &mut [Nurse], rng: &mut GaRng, info: &Info, config: &Config) {
     let nurse_i = rng.random_range(0..nurses.len());
     let nurse_len = nurses.len();
 
@@ -168,18 +170,16 @@ fn heurisitc_random_cross_swap_mutation2(nurses: &mut Vec<Nurse>, rng: &mut Thre
     }
 
     let mut fitnesses: Vec<SwapFitness> = Vec::new();
+    let cache_i = RouteCache::new(&nurses[nurse_i].route, info, config);
+    let cache_j = RouteCache::new(&nurses[nurse_j].route, info, config);
 
     // Evaluate only the random swaps
     for (patient_i, patient_j) in random_swaps {
-        let patient_1 = *nurses[nurse_i].route.get(patient_i).unwrap();
-        let patient_2 = *nurses[nurse_j].route.get(patient_j).unwrap();
-
-        // Swap
-        nurses[nurse_i].route[patient_i] = patient_2;
-        nurses[nurse_j].route[patient_j] = patient_1;
+        let patient_1 = nurses[nurse_i].route[patient_i];
+        let patient_2 = nurses[nurse_j].route[patient_j];
 
-        let fitness = fitness_nurse(&nurses[nurse_i], &info, &config).0
-            + fitness_nurse(&nurses[nurse_j], &info, &config).0;
+        let fitness = cache_i.cost + cache_i.replace_delta(&nurses[nurse_i].route, patient_i, patient_2, info, config)
+            + cache_j.cost + cache_j.replace_delta(&nurses[nurse_j].route, patient_j, patient_1, info, config);
 
         if fitnesses.is_empty() {
             fitnesses.push(SwapFitness{i: patient_i, j: patient_j, fitness})
@@ -200,19 +200,14 @@ fn heurisitc_random_cross_swap_mutation2(nurses: &mut Vec<Nurse>, rng: &mut Thre
                 fitnesses.insert(insertion_idx, SwapFitness{i: patient_i, j: patient_j, fitness});
             }
         }
-
-        // Swap back
-        nurses[nurse_i].route[patient_i] = patient_1;
-        nurses[nurse_j].route[patient_j] = patient_2;
     }
 
     let mu = fitnesses.len();
-    let swap;
     if mu == 0 {
         return;
     }
-    if mu < 2 {
-        swap = fitnesses.first().unwrap();
+    let swap = if mu < 2 {
+        fitnesses.first().unwrap()
     } else {
         let probabilities: Vec<f32> = fitnesses
             .iter()
@@ -224,8 +219,8 @@ fn heurisitc_random_cross_swap_mutation2(nurses: &mut Vec<Nurse>, rng: &mut Thre
 
         let swap_idx = dist.sample(rng);
 
-        swap = &fitnesses[swap_idx];
-    }
+        &fitnesses[swap_idx]
+    };
 
     // Do final swap
     let temp = nurses[nurse_i].route[swap.i];
@@ -233,7 +228,7 @@ fn heurisitc_random_cross_swap_mutation2(nurses: &mut Vec<Nurse>, rng: &mut Thre
     nurses[nurse_j].route[swap.j] = temp;
 }
 
-fn heuristic_insert_mutation(nurses: &mut Vec<Nurse>, rng: &mut ThreadRng, info: &Info, config: &Config) {
+pub fn heuristic_insert_mutation(nurses: &mut [Nurse], rng: &mut GaRng, info: &Info, config: &Config) {
     let nurse_to_change_idx = rng.random_range(0..nurses.len());
     if nurses[nurse_to_change_idx].route.is_empty() {
         return;
@@ -262,12 +257,12 @@ fn heuristic_insert_mutation(nurses: &mut Vec<Nurse>, rng: &mut ThreadRng, info:
     let mut best_fitness: f32 = f32::MAX;
 
     for (nurse_idx, patient_idx) in zip(&nurse_indices, &patient_indices) {
-        let fitness_before = fitness_nurse(&nurses[*nurse_idx], &info, &config).0;
+        let fitness_before = fitness_nurse(&nurses[*nurse_idx], info, config).0;
 
         nurses[nurse_to_change_idx].route.remove(patient_to_change_idx);
         nurses[*nurse_idx].route.insert(*patient_idx, patient_to_change);
 
-        let fitness_after = fitness_nurse(&nurses[*nurse_idx], &info, &config).0;
+        let fitness_after = fitness_nurse(&nurses[*nurse_idx], info, config).0;
 
         nurses[*nurse_idx].route.remove(*patient_idx);
         nurses[nurse_to_change_idx].route.insert(patient_to_change_idx, patient_to_change);
@@ -287,4 +282,99 @@ fn heuristic_insert_mutation(nurses: &mut Vec<Nurse>, rng: &mut ThreadRng, info:
         nurses[nurse_to_change_idx].route.remove(patient_to_change_idx);
         nurses[swap.i].route.insert(swap.j, patient_to_change);
     }
-}
\ No newline at end of file
+}
+
+/// Number of random moves the 2-opt* and cross-exchange mutations pick the best of
+const SAMPLED_MOVES: usize = 10;
+
+/// Two different nurses, the first with a route. `None` if there are no two such nurses
+fn two_nurses(nurses: &[Nurse], rng: &mut GaRng) -> Option<(usize, usize)> {
+    let with_route: Vec<usize> = (0..nurses.len()).filter(|i| !nurses[*i].route.is_empty()).collect();
+    if with_route.is_empty() || nurses.len() < 2 {
+        return None;
+    }
+    let a = with_route[rng.random_range(0..with_route.len())];
+    let b = (a + rng.random_range(1..nurses.len())) % nurses.len();
+    Some((a, b))
+}
+
+/// Exchange the tails of two routes (2-opt*), the best of a few random cuts
+pub fn two_opt_star_mutation(nurses: &mut [Nurse], rng: &mut GaRng, info: &Info, config: &Config) {
+    let Some((a, b)) = two_nurses(nurses, rng) else { return };
+    let (len_a, len_b) = (nurses[a].route.len(), nurses[b].route.len());
+    let cache_a = RouteCache::new(&nurses[a].route, info, config);
+    let cache_b = RouteCache::new(&nurses[b].route, info, config);
+
+    let mut best: Option<(f32, usize, usize)> = None;
+    for _ in 0..SAMPLED_MOVES {
+        let i = rng.random_range(0..=len_a);
+        let j = rng.random_range(0..=len_b);
+        let (tail_a, tail_b) = (&nurses[a].route[i..], &nurses[b].route[j..]);
+        let delta = cache_a.splice_delta(&nurses[a].route, i, len_a, tail_b, info, config)
+            + cache_b.splice_delta(&nurses[b].route, j, len_b, tail_a, info, config);
+        if best.is_none_or(|(best_delta, _, _)| delta < best_delta) {
+            best = Some((delta, i, j));
+        }
+    }
+
+    let (_, i, j) = best.unwrap();
+    let tail_a = nurses[a].route.split_off(i);
+    let tail_b = nurses[b].route.split_off(j);
+    nurses[a].route.extend(tail_b);
+    nurses[b].route.extend(tail_a);
+}
+
+/// Exchange segments of up to `cross_exchange_max_length` patients between two routes (CROSS),
+/// reversed too with `cross_exchange_reverse`. The best of a few random exchanges
+pub fn cross_exchange_mutation(nurses: &mut [Nurse], rng: &mut GaRng, info: &Info, config: &Config) {
+    let Some((a, b)) = two_nurses(nurses, rng) else { return };
+    if nurses[b].route.is_empty() || config.cross_exchange_max_length == 0 {
+        return;
+    }
+    let cache_a = RouteCache::new(&nurses[a].route, info, config);
+    let cache_b = RouteCache::new(&nurses[b].route, info, config);
+    // Segment of `b` going into `a`, and of `a` going into `b`
+    let segments = |nurses: &[Nurse], exchange: &CrossExchange| {
+        let mut into_a = nurses[b].route[exchange.j..exchange.j + exchange.length_b].to_vec();
+        let mut into_b = nurses[a].route[exchange.i..exchange.i + exchange.length_a].to_vec();
+        if exchange.reverse {
+            into_a.reverse();
+            into_b.reverse();
+        }
+        (into_a, into_b)
+    };
+
+    let mut best: Option<(f32, CrossExchange)> = None;
+    for _ in 0..SAMPLED_MOVES {
+        let (route_a, route_b) = (&nurses[a].route, &nurses[b].route);
+        let i = rng.random_range(0..route_a.len());
+        let j = rng.random_range(0..route_b.len());
+        let exchange = CrossExchange {
+            i,
+            j,
+            length_a: rng.random_range(1..=config.cross_exchange_max_length.min(route_a.len() - i)),
+            length_b: rng.random_range(1..=config.cross_exchange_max_length.min(route_b.len() - j)),
+            reverse: config.cross_exchange_reverse && rng.random_bool(0.5),
+        };
+        let (into_a, into_b) = segments(nurses, &exchange);
+        let delta = cache_a.splice_delta(route_a, i, i + exchange.length_a, &into_a, info, config)
+            + cache_b.splice_delta(route_b, j, j + exchange.length_b, &into_b, info, config);
+        if best.as_ref().is_none_or(|(best_delta, _)| delta < *best_delta) {
+            best = Some((delta, exchange));
+        }
+    }
+
+    let (_, exchange) = best.unwrap();
+    let (into_a, into_b) = segments(nurses, &exchange);
+    nurses[a].route.splice(exchange.i..exchange.i + exchange.length_a, into_a);
+    nurses[b].route.splice(exchange.j..exchange.j + exchange.length_b, into_b);
+}
+
+/// Segments at `i` in one route and at `j` in the other
+struct CrossExchange {
+    i: usize,
+    j: usize,
+    length_a: usize,
+    length_b: usize,
+    reverse: bool,
+}
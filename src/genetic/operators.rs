@@ -0,0 +1,225 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::Arc;
+
+use crate::genetic::crossover::{edge_recombination_crossover, order_crossover, route_based_crossover, srex_crossover, visma_crossover};
+use crate::genetic::intra_route::{or_opt_mutation, two_opt_mutation};
+use crate::genetic::large_neighborhood::destroy_and_repair;
+use crate::genetic::mutation::{cross_exchange_mutation, heurisitc_random_cross_swap_mutation2, heuristic_cluster_mutation, heuristic_insert_mutation, heuristic_swap_mutation, two_opt_star_mutation};
+use crate::genetic::random::GaRng;
+use crate::genetic::ruin_recreate::ruin_and_recreate;
+use crate::genetic::parent_selection::{linear_ranking, probabilistic_ranking, tournament};
+use crate::genetic::scramble::{delete, keep};
+use crate::genetic::survivor_selection::{crowding, crowding_optimized};
+use crate::structs::config::Config;
+use crate::structs::io::Info;
+use crate::structs::nurse::{Individual, Nurse};
+
+/// Changes the routes of one individual in place
+pub trait Mutation: Send + Sync {
+    fn mutate(&self, nurses: &mut [Nurse], rng: &mut GaRng, info: &Info, config: &Config);
+}
+
+/// Creates two children from two parents
+pub trait Crossover: Send + Sync {
+    fn crossover(&self, parent1: &Individual, parent2: &Individual, rng: &mut GaRng, info: &Info, config: &Config) -> (Individual, Individual);
+}
+
+/// Returns indices of the parents. The population is sorted with the best individual last
+pub trait ParentSelection: Send + Sync {
+    fn select(&self, population: &[Individual], rng: &mut GaRng, config: &Config) -> Vec<usize>;
+}
+
+/// Replaces members of the population with children. Children `2i` and `2i+1`
+/// come from parents `parent_indices[2i]` and `parent_indices[2i+1]`
+pub trait SurvivorSelection: Send + Sync {
+    fn select(&self, population: &mut [Individual], parent_indices: &[usize], children: &[Individual], rng: &mut GaRng, config: &Config);
+}
+
+/// Restarts a stagnated population. The population is sorted with the best individual last
+pub trait RestartStrategy: Send + Sync {
+    fn restart(&self, population: &mut Vec<Individual>, rng: &mut GaRng, info: &Info, config: &Config);
+}
+
+impl<F> Mutation for F
+where
+    F: Fn(&mut [Nurse], &mut GaRng, &Info, &Config) + Send + Sync,
+{
+    fn mutate(&self, nurses: &mut [Nurse], rng: &mut GaRng, info: &Info, config: &Config) {
+        self(nurses, rng, info, config)
+    }
+}
+
+impl<F> Crossover for F
+where
+    F: Fn(&Individual, &Individual, &mut GaRng, &Info, &Config) -> (Individual, Individual) + Send + Sync,
+{
+    fn crossover(&self, parent1: &Individual, parent2: &Individual, rng: &mut GaRng, info: &Info, config: &Config) -> (Individual, Individual) {
+        self(parent1, parent2, rng, info, config)
+    }
+}
+
+impl<F> ParentSelection for F
+where
+    F: Fn(&[Individual], &mut GaRng, &Config) -> Vec<usize> + Send + Sync,
+{
+    fn select(&self, population: &[Individual], rng: &mut GaRng, config: &Config) -> Vec<usize> {
+        self(population, rng, config)
+    }
+}
+
+impl<F> SurvivorSelection for F
+where
+    F: Fn(&mut [Individual], &[usize], &[Individual], &mut GaRng, &Config) + Send + Sync,
+{
+    fn select(&self, population: &mut [Individual], parent_indices: &[usize], children: &[Individual], rng: &mut GaRng, config: &Config) {
+        self(population, parent_indices, children, rng, config)
+    }
+}
+
+impl<F> RestartStrategy for F
+where
+    F: Fn(&mut Vec<Individual>, &mut GaRng, &Info, &Config) + Send + Sync,
+{
+    fn restart(&self, population: &mut Vec<Individual>, rng: &mut GaRng, info: &Info, config: &Config) {
+        self(population, rng, info, config)
+    }
+}
+
+/// Operators picked out of the registry for one run
+#[derive(Clone)]
+pub struct Operators {
+    pub mutations: Vec<(Arc<dyn Mutation>, f32)>,
+    pub crossover: Arc<dyn Crossover>,
+    pub parent_selection: Arc<dyn ParentSelection>,
+    pub survivor_selection: Arc<dyn SurvivorSelection>,
+    pub restart: Arc<dyn RestartStrategy>,
+}
+
+/// Operators by name. Built-in crossovers, selections and restarts are registered under
+/// the names of their config variants (e.g. `Visma`, `LinearRanking`, `Crowding`, `Delete`),
+/// and built-in mutations under the prefix of their rate field (e.g. `heuristic_cluster`).
+#[derive(Clone)]
+pub struct OperatorRegistry {
+    mutations: HashMap<String, Arc<dyn Mutation>>,
+    crossovers: HashMap<String, Arc<dyn Crossover>>,
+    parent_selections: HashMap<String, Arc<dyn ParentSelection>>,
+    survivor_selections: HashMap<String, Arc<dyn SurvivorSelection>>,
+    restarts: HashMap<String, Arc<dyn RestartStrategy>>,
+}
+
+type MutationFN = fn(&mut [Nurse], &mut GaRng, &Info, &Config);
+type CrossoverFNType = fn(&Individual, &Individual, &mut GaRng, &Info, &Config) -> (Individual, Individual);
+type RankingFN = fn(&[Individual], &mut GaRng, &Config) -> Vec<usize>;
+type SurvivorFN = fn(&mut [Individual], &[usize], &[Individual], &mut GaRng, &Config);
+type ScrambleFNType = fn(&mut Vec<Individual>, &mut GaRng, &Info, &Config);
+
+impl Default for OperatorRegistry {
+    fn default() -> Self {
+        let mut registry = OperatorRegistry::empty();
+
+        registry.register_mutation("heuristic_cluster", heuristic_cluster_mutation as MutationFN);
+        registry.register_mutation("heuristic_swap", heuristic_swap_mutation as MutationFN);
+        registry.register_mutation("heuristic_random_swap", heurisitc_random_cross_swap_mutation2 as MutationFN);
+        registry.register_mutation("large_neighbourhood", destroy_and_repair as MutationFN);
+        registry.register_mutation("insert", heuristic_insert_mutation as MutationFN);
+        registry.register_mutation("two_opt_star", two_opt_star_mutation as MutationFN);
+        registry.register_mutation("cross_exchange", cross_exchange_mutation as MutationFN);
+        registry.register_mutation("two_opt", two_opt_mutation as MutationFN);
+        registry.register_mutation("or_opt", or_opt_mutation as MutationFN);
+        registry.register_mutation("ruin_recreate", ruin_and_recreate as MutationFN);
+
+        registry.register_crossover("Visma", visma_crossover as CrossoverFNType);
+        registry.register_crossover("Order", order_crossover as CrossoverFNType);
+        registry.register_crossover("EdgeRecombination", edge_recombination_crossover as CrossoverFNType);
+        registry.register_crossover("RouteBased", route_based_crossover as CrossoverFNType);
+        registry.register_crossover("Srex", srex_crossover as CrossoverFNType);
+
+        registry.register_parent_selection("LinearRanking", linear_ranking as RankingFN);
+        registry.register_parent_selection("Probabilistic", probabilistic_ranking as RankingFN);
+        registry.register_parent_selection("Tournament", tournament as RankingFN);
+
+        registry.register_survivor_selection("CrowdingOld", (|population: &mut [Individual], _: &[usize], children: &[Individual], rng: &mut GaRng, config: &Config| {
+            crowding(population, children, rng, config)
+        }) as SurvivorFN);
+        registry.register_survivor_selection("Crowding", (|population: &mut [Individual], parent_indices: &[usize], children: &[Individual], rng: &mut GaRng, config: &Config| {
+            crowding_optimized(population, children, rng, config, parent_indices)
+        }) as SurvivorFN);
+
+        registry.register_restart("Delete", delete as ScrambleFNType);
+        registry.register_restart("Keep", keep as ScrambleFNType);
+
+        registry
+    }
+}
+
+impl OperatorRegistry {
+    /// Registry without any operators
+    pub fn empty() -> Self {
+        OperatorRegistry {
+            mutations: HashMap::new(),
+            crossovers: HashMap::new(),
+            parent_selections: HashMap::new(),
+            survivor_selections: HashMap::new(),
+            restarts: HashMap::new(),
+        }
+    }
+
+    pub fn register_mutation(&mut self, name: &str, mutation: impl Mutation + 'static) {
+        self.mutations.insert(name.to_string(), Arc::new(mutation));
+    }
+
+    pub fn register_crossover(&mut self, name: &str, crossover: impl Crossover + 'static) {
+        self.crossovers.insert(name.to_string(), Arc::new(crossover));
+    }
+
+    pub fn register_parent_selection(&mut self, name: &str, selection: impl ParentSelection + 'static) {
+        self.parent_selections.insert(name.to_string(), Arc::new(selection));
+    }
+
+    pub fn register_survivor_selection(&mut self, name: &str, selection: impl SurvivorSelection + 'static) {
+        self.survivor_selections.insert(name.to_string(), Arc::new(selection));
+    }
+
+    pub fn register_restart(&mut self, name: &str, restart: impl RestartStrategy + 'static) {
+        self.restarts.insert(name.to_string(), Arc::new(restart));
+    }
+
+    pub fn mutation_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.mutations.keys().map(|name| name.as_str()).collect();
+        names.sort();
+        names
+    }
+
+    /// Look up the operators named in the config. Mutations with rate 0 are left out
+    pub fn resolve(&self, config: &Config) -> Result<Operators, Box<dyn Error>> {
+        let mut mutations = Vec::new();
+        for (name, rate) in config.mutation_rates() {
+            let mutation = lookup(&self.mutations, "mutation", &name)?;
+            // A mutation that never runs would still draw a random number per child, so adding
+            // one with rate 0 would change the results of seeded runs
+            if rate > 0. {
+                mutations.push((mutation, rate));
+            }
+        }
+
+        Ok(Operators {
+            mutations,
+            crossover: lookup(&self.crossovers, "crossover", config.crossover_fn.name())?,
+            parent_selection: lookup(&self.parent_selections, "parent selection", config.parent_selection_fn.name())?,
+            survivor_selection: lookup(&self.survivor_selections, "survivor selection", config.survivor_selection_fn.name())?,
+            restart: lookup(&self.restarts, "restart strategy", config.scramble_fn.name())?,
+        })
+    }
+}
+
+fn lookup<T: ?Sized>(operators: &HashMap<String, Arc<T>>, kind: &str, name: &str) -> Result<Arc<T>, Box<dyn Error>> {
+    match operators.get(name) {
+        Some(operator) => Ok(Arc::clone(operator)),
+        None => {
+            let mut known: Vec<&String> = operators.keys().collect();
+            known.sort();
+            Err(format!("Unknown {} '{}', registered: {:?}", kind, name, known).into())
+        }
+    }
+}
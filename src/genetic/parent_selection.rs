@@ -1,23 +1,25 @@
-use crate::structs::config::{Config, ParentSelectionFN};
+use crate::genetic::diversity::biased_tournament;
+use crate::genetic::operators::ParentSelection;
+use crate::genetic::random::GaRng;
+use crate::structs::config::Config;
 use crate::structs::nurse::Individual;
 
-use rand::rng;
-use rand::rngs::ThreadRng;
 use rand::distr::Distribution;
 use rand::distr::weighted::WeightedIndex;
 use rand::prelude::SliceRandom;
 
-type RankingFN = fn(&Vec<Individual>, &Config) -> Vec<usize>;
-
-/// Do parent selection based on config and get parent indices
-pub fn parent_selection(population: &mut Vec<Individual>, config: &Config) -> Vec<usize> {
-    let parent_fn: RankingFN = match config.parent_selection_fn {
-        ParentSelectionFN::LinearRanking => linear_ranking,
-        ParentSelectionFN::Probabilistic => probabilistic_ranking,
-        ParentSelectionFN::Tournament => tournament
+/// Do parent selection based on config and get parent indices.
+/// With `biased_fitness` the parents are picked by binary tournaments on biased fitness instead.
+/// A population of one, left after clones are removed, is paired with itself
+pub fn parent_selection(population: &[Individual], rng: &mut GaRng, config: &Config, selection: &dyn ParentSelection) -> Vec<usize> {
+    if population.len() < 2 {
+        return population.iter().flat_map(|_| [0, 0]).collect();
+    }
+    let mut parent_indices = if config.biased_fitness {
+        biased_tournament(population, rng, config)
+    } else {
+        selection.select(population, rng, config)
     };
-
-    let mut parent_indices = parent_fn(population, &config);
     if (parent_indices.len() & 1) != 0 {
         parent_indices.remove(0);
     }
@@ -31,10 +33,9 @@ pub fn linear_rank_probability(mu: usize, s: f32, i: usize) -> f32 {
 
 /// Return indices of the best individuals_9.
 /// Population must be sorted
-pub fn linear_ranking(population: &Vec<Individual>, config: &Config) -> Vec<usize> {
+pub fn linear_ranking(population: &[Individual], rng: &mut GaRng, config: &Config) -> Vec<usize> {
     let mu = population.len();
-    let mut rng: ThreadRng = rng();
-    let n_parents = ((population.len() - config.n_elitism as usize) as f32 * config.n_parents_scaling) as usize;
+    let n_parents = (population.len().saturating_sub(config.n_elitism as usize) as f32 * config.n_parents_scaling) as usize;
 
     let probabilities: Vec<f32> = population
         .iter()
@@ -46,15 +47,14 @@ pub fn linear_ranking(population: &Vec<Individual>, config: &Config) -> Vec<usiz
 
     let mut indices = Vec::new();
     for _ in 0..n_parents {
-        indices.push(dist.sample(&mut rng))
+        indices.push(dist.sample(rng))
     }
 
     indices
 }
 
-pub fn probabilistic_ranking(population: &Vec<Individual>, config: &Config) -> Vec<usize> {
-    let mut rng: ThreadRng = rng();
-    let n_parents: usize = (population.len() - config.n_elitism as usize) * 3;
+pub fn probabilistic_ranking(population: &[Individual], rng: &mut GaRng, config: &Config) -> Vec<usize> {
+    let n_parents: usize = population.len().saturating_sub(config.n_elitism as usize) * 3;
     let sum: f32 = population
         .iter()
         .map(|individual| individual.fitness)
@@ -69,22 +69,21 @@ pub fn probabilistic_ranking(population: &Vec<Individual>, config: &Config) -> V
 
     let mut indices = Vec::new();
     for _ in 0..n_parents {
-        indices.push(dist.sample(&mut rng))
+        indices.push(dist.sample(rng))
     }
 
     indices
 }
 
-fn tournament(population: &Vec<Individual>, config: &Config) -> Vec<usize> {
-    let mut rng: ThreadRng = rand::rng();
+pub fn tournament(population: &[Individual], rng: &mut GaRng, config: &Config) -> Vec<usize> {
     let tournament_size = config.tournament_size.min(population.len() as i32) as usize;
-    let n_parents = ((population.len() - config.n_elitism as usize) as f32 * config.n_parents_scaling) as usize;
+    let n_parents = (population.len().saturating_sub(config.n_elitism as usize) as f32 * config.n_parents_scaling) as usize;
 
     let mut parent_indices = Vec::with_capacity(n_parents);
 
     for _ in 0..n_parents {
         let mut competitors: Vec<usize> = (0..population.len()).collect();
-        competitors.shuffle(&mut rng);
+        competitors.shuffle(rng);
         let competitors = &competitors[0..tournament_size];
 
         let winner = competitors.iter()
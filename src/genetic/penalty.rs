@@ -0,0 +1,70 @@
+use crate::genetic::evaluate::Segment;
+use crate::structs::config::{Config, PenaltyModel};
+use crate::structs::io::Info;
+use crate::structs::nurse::Individual;
+
+/// Factor a penalty weight is raised by when too few children are feasible
+const PENALTY_INCREASE: f32 = 1.2;
+/// Factor a penalty weight is lowered by when too many children are feasible
+const PENALTY_DECREASE: f32 = 0.85;
+/// Ratios this close to the target leave the weights alone
+const RATIO_TOLERANCE: f32 = 0.05;
+const MIN_PENALTY: f32 = 0.1;
+const MAX_PENALTY: f32 = 100_000.;
+
+/// Adapts the penalty weights to how many recent children meet the time windows and the
+/// capacity, aiming for `target_feasible_ratio` of each, like hybrid genetic search does.
+#[derive(Debug, Default)]
+pub(crate) struct PenaltyManager {
+    children: usize,
+    time_window_feasible: usize,
+    load_feasible: usize,
+}
+
+impl PenaltyManager {
+    /// Count the children that meet the time windows and the capacity
+    pub(crate) fn record(&mut self, children: &[Individual], info: &Info) {
+        for child in children {
+            self.children += 1;
+            if child.feasible {
+                self.time_window_feasible += 1;
+                self.load_feasible += 1;
+                continue;
+            }
+            let routes: Vec<Segment> = child.nurses.iter().map(|nurse| Segment::of_route(&nurse.route, info)).collect();
+            self.time_window_feasible += routes.iter().all(|route| route.time_warp <= 0.) as usize;
+            self.load_feasible += routes.iter().all(|route| route.load <= info.capacity_nurse) as usize;
+        }
+    }
+
+    /// Move the weights in `config` towards the target ratio. Returns whether any weight changed,
+    /// in which case the population has to be evaluated again.
+    pub(crate) fn adjust(&mut self, config: &mut Config) -> bool {
+        if self.children == 0 {
+            return false;
+        }
+        let time_window_ratio = self.time_window_feasible as f32 / self.children as f32;
+        let load_ratio = self.load_feasible as f32 / self.children as f32;
+        let target = config.target_feasible_ratio;
+        *self = PenaltyManager::default();
+
+        match config.penalty_model {
+            PenaltyModel::TimeWarp => {
+                let time_window_changed = adjust_weight(&mut config.time_warp_penalty, time_window_ratio, target);
+                adjust_weight(&mut config.load_penalty, load_ratio, target) || time_window_changed
+            }
+            // The legacy capacity and depot penalties are fixed, only lateness is weighted
+            PenaltyModel::Legacy => adjust_weight(&mut config.fitness_punishment_factor, time_window_ratio, target),
+        }
+    }
+}
+
+fn adjust_weight(weight: &mut f32, feasible_ratio: f32, target: f32) -> bool {
+    let old = *weight;
+    if feasible_ratio < target - RATIO_TOLERANCE {
+        *weight = (*weight * PENALTY_INCREASE).min(MAX_PENALTY);
+    } else if feasible_ratio > target + RATIO_TOLERANCE {
+        *weight = (*weight * PENALTY_DECREASE).max(MIN_PENALTY);
+    }
+    *weight != old
+}
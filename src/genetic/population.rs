@@ -0,0 +1,98 @@
+use crate::genetic::diversity::{biased_fitness, Neighbours};
+use crate::structs::config::Config;
+use crate::structs::nurse::Individual;
+
+/// Population split into feasible and infeasible individuals, each with its own size limit,
+/// so infeasible individuals can be explored without pushing out the feasible ones.
+#[derive(Debug, Default)]
+pub(crate) struct Subpopulations {
+    pub(crate) feasible: Vec<Individual>,
+    pub(crate) infeasible: Vec<Individual>,
+}
+
+impl Subpopulations {
+    pub(crate) fn new(individuals: impl IntoIterator<Item = Individual>) -> Self {
+        let mut subpopulations = Subpopulations::default();
+        for individual in individuals {
+            subpopulations.add(individual);
+        }
+        subpopulations
+    }
+
+    pub(crate) fn add(&mut self, individual: Individual) {
+        if individual.feasible {
+            self.feasible.push(individual);
+        } else {
+            self.infeasible.push(individual);
+        }
+    }
+
+    /// Keep the best distinct individuals of each subpopulation, up to its size limit.
+    /// Best by biased fitness when `biased_fitness` is set, otherwise by fitness
+    pub(crate) fn select_survivors(&mut self, config: &Config) {
+        for (individuals, size) in [
+            (&mut self.feasible, config.feasible_population_size),
+            (&mut self.infeasible, config.infeasible_population_size),
+        ] {
+            if config.biased_fitness {
+                remove_clones(individuals);
+                keep_best_biased(individuals, size.max(0) as usize, config);
+            } else {
+                keep_best(individuals, size.max(0) as usize);
+            }
+        }
+    }
+
+    /// Both subpopulations as one population to select parents from
+    pub(crate) fn into_population(self) -> Vec<Individual> {
+        let mut population = self.infeasible;
+        population.extend(self.feasible);
+        population
+    }
+}
+
+/// Remove individuals with the same fitness as an earlier one and at broken-pairs distance 0 from
+/// it. Those have the same routes, maybe with the nurses in another order or routes driven the
+/// other way round
+fn remove_clones(individuals: &mut Vec<Individual>) {
+    let mut kept: Vec<Individual> = Vec::with_capacity(individuals.len());
+    let mut kept_neighbours: Vec<Neighbours> = Vec::with_capacity(individuals.len());
+    for individual in std::mem::take(individuals) {
+        let neighbours = Neighbours::new(&individual);
+        let is_clone = kept.iter().zip(&kept_neighbours)
+            .any(|(other, other_neighbours)| other.fitness == individual.fitness && neighbours.distance(other_neighbours) == 0.);
+        if !is_clone {
+            kept.push(individual);
+            kept_neighbours.push(neighbours);
+        }
+    }
+    *individuals = kept;
+}
+
+/// Remove the individuals with the worst biased fitness. Removing one changes the diversity of
+/// the rest, so at most half of the surplus is removed before biased fitness is computed again.
+fn keep_best_biased(individuals: &mut Vec<Individual>, size: usize, config: &Config) {
+    while individuals.len() > size {
+        let surplus = individuals.len() - size;
+        let biased = biased_fitness(individuals, config);
+        let mut order: Vec<usize> = (0..individuals.len()).collect();
+        order.sort_by(|a, b| biased[*a].total_cmp(&biased[*b]));
+
+        let mut remove = vec![false; individuals.len()];
+        for &index in order.iter().rev().take(surplus.div_ceil(2)) {
+            remove[index] = true;
+        }
+        let mut index = 0;
+        individuals.retain(|_| {
+            index += 1;
+            !remove[index - 1]
+        });
+    }
+}
+
+fn keep_best(individuals: &mut Vec<Individual>, size: usize) {
+    individuals.sort_by(|a, b| a.fitness.total_cmp(&b.fitness));
+    // Clones only take up room
+    remove_clones(individuals);
+    individuals.truncate(size);
+}
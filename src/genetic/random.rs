@@ -0,0 +1,20 @@
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+
+/// Random number generator passed to every operator
+pub type GaRng = StdRng;
+
+/// Generator for a whole run. Without a seed it is seeded from the thread generator
+pub fn new_rng(seed: Option<u64>) -> GaRng {
+    match seed {
+        Some(seed) => GaRng::seed_from_u64(seed),
+        None => GaRng::from_rng(&mut rand::rng()),
+    }
+}
+
+/// Generator for a sub task such as an island or a child made on another thread.
+/// Derived from the parent generator, so seeded runs stay reproducible
+/// no matter which thread ends up doing the work.
+pub fn child_rng(rng: &mut GaRng) -> GaRng {
+    GaRng::seed_from_u64(rng.next_u64())
+}
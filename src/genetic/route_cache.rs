@@ -0,0 +1,177 @@
+use std::sync::OnceLock;
+
+use crate::genetic::evaluate::{fitness_nurse, penalized_cost, Segment};
+use crate::structs::config::PenaltyModel;
+use crate::structs::config::Config;
+use crate::structs::io::Info;
+use crate::structs::nurse::Nurse;
+
+/// Cached schedule of one route, so the cost of changing a few patients can be found without
+/// walking the whole route again.
+///
+/// It holds a `Segment` for every prefix (depot up to a position) and every suffix (a position
+/// back to the depot). Splicing new patients into the route is then one concatenation of a prefix,
+/// the new patients and a suffix. With the `TimeWarp` penalty model that gives the cost of the
+/// changed route directly. With the legacy penalties only a feasible route's cost is known, as it is
+/// just the travel time; otherwise the delta falls back to evaluating the changed route in full.
+/// So deltas are O(1) with `TimeWarp`, but O(n) for moves that leave a legacy route infeasible
+/// (see `benches/route_cache.rs`).
+///
+/// Swaps also need the patients between the two positions. Their segments come from a table
+/// built on the first swap, after which every swap is O(1) as well.
+#[derive(Debug, Clone)]
+pub struct RouteCache {
+    /// `prefix[i]` is the depot followed by the first `i` patients
+    prefix: Vec<Segment>,
+    /// `suffix[i]` is the patients from position `i` followed by the depot
+    suffix: Vec<Segment>,
+    /// `sub_segments` of the route, built when first needed
+    between: OnceLock<Vec<Vec<Segment>>>,
+    pub load: u32,
+    /// `fitness_nurse` of the route
+    pub cost: f32,
+    pub feasible: bool,
+}
+
+impl RouteCache {
+    pub fn new(route: &[i32], info: &Info, config: &Config) -> Self {
+        let depot = Segment::depot(info);
+        let mut prefix = Vec::with_capacity(route.len() + 1);
+        prefix.push(depot);
+        for &p in route {
+            prefix.push(prefix[prefix.len() - 1].concat(&Segment::patient(p, info), info));
+        }
+        let mut suffix = vec![depot; route.len() + 1];
+        for (position, &p) in route.iter().enumerate().rev() {
+            suffix[position] = Segment::patient(p, info).concat(&suffix[position + 1], info);
+        }
+
+        let whole = prefix[route.len()].concat(&depot, info);
+        let cost = fitness_nurse(&Nurse { capacity: 0, route: route.to_vec() }, info, config).0;
+        RouteCache { prefix, suffix, between: OnceLock::new(), load: whole.load, cost, feasible: whole.is_feasible(info) }
+    }
+
+    /// Change in `fitness_nurse` from replacing `route[start..end]` with `replacement`.
+    /// `route` must be the route the cache was built from.
+    pub fn splice_delta(&self, route: &[i32], start: usize, end: usize, replacement: &[i32], info: &Info, config: &Config) -> f32 {
+        let segment = Segment::of_patients(replacement, info);
+        self.splice_segment_delta(route, start, end, replacement, segment.as_ref(), info, config)
+    }
+
+    /// Like `splice_delta`, with the segment of `replacement` already built. Lets an operator
+    /// try the same patients at many positions in constant time each.
+    #[allow(clippy::too_many_arguments)]
+    pub fn splice_segment_delta(
+        &self,
+        route: &[i32],
+        start: usize,
+        end: usize,
+        replacement: &[i32],
+        segment: Option<&Segment>,
+        info: &Info,
+        config: &Config,
+    ) -> f32 {
+        let spliced = self.spliced(start, end, segment, info);
+        self.delta(&spliced, info, config, || {
+            let mut changed = Vec::with_capacity(route.len() - (end - start) + replacement.len());
+            changed.extend_from_slice(&route[..start]);
+            changed.extend_from_slice(replacement);
+            changed.extend_from_slice(&route[end..]);
+            changed
+        })
+    }
+
+    /// Change in `fitness_nurse` for the changed route with segment `changed`. `route` builds its
+    /// patients, which is only needed for an infeasible route with the legacy penalties.
+    fn delta(&self, changed: &Segment, info: &Info, config: &Config, route: impl FnOnce() -> Vec<i32>) -> f32 {
+        if config.penalty_model == PenaltyModel::TimeWarp {
+            return penalized_cost(changed, info, config) - self.cost;
+        }
+        if changed.is_feasible(info) {
+            return changed.distance - self.cost;
+        }
+        fitness_nurse(&Nurse { capacity: 0, route: route() }, info, config).0 - self.cost
+    }
+
+    /// The whole route, from and back to the depot, with `route[start..end]` replaced by the
+    /// patients of `segment`
+    pub fn spliced(&self, start: usize, end: usize, segment: Option<&Segment>, info: &Info) -> Segment {
+        let head = match segment {
+            Some(segment) => self.prefix[start].concat(segment, info),
+            None => self.prefix[start],
+        };
+        head.concat(&self.suffix[end], info)
+    }
+
+    /// Change in cost from inserting `patients` in front of `position`
+    pub fn insert_delta(&self, route: &[i32], position: usize, patients: &[i32], info: &Info, config: &Config) -> f32 {
+        self.splice_delta(route, position, position, patients, info, config)
+    }
+
+    /// Change in cost from removing `route[start..end]`
+    pub fn remove_delta(&self, route: &[i32], start: usize, end: usize, info: &Info, config: &Config) -> f32 {
+        self.splice_delta(route, start, end, &[], info, config)
+    }
+
+    /// Change in cost from visiting `patient` instead of the patient at `position`
+    pub fn replace_delta(&self, route: &[i32], position: usize, patient: i32, info: &Info, config: &Config) -> f32 {
+        self.splice_delta(route, position, position + 1, &[patient], info, config)
+    }
+
+    /// Change in cost from swapping the patients at positions `i` and `j`
+    pub fn swap_delta(&self, route: &[i32], i: usize, j: usize, info: &Info, config: &Config) -> f32 {
+        let (i, j) = (i.min(j), i.max(j));
+        if i == j {
+            return 0.;
+        }
+        let mut swapped = self.prefix[i].concat(&Segment::patient(route[j], info), info);
+        if let Some(middle) = self.between(route, i + 1, j, info) {
+            swapped = swapped.concat(&middle, info);
+        }
+        swapped = swapped.concat(&Segment::patient(route[i], info), info).concat(&self.suffix[j + 1], info);
+        self.delta(&swapped, info, config, || {
+            let mut changed = route.to_vec();
+            changed.swap(i, j);
+            changed
+        })
+    }
+
+    /// Segment of `route[start..end]`, or `None` if it is empty
+    fn between(&self, route: &[i32], start: usize, end: usize, info: &Info) -> Option<Segment> {
+        if start >= end {
+            return None;
+        }
+        let (first, last) = (start, end - 1);
+        if first == last {
+            return Some(Segment::patient(route[first], info));
+        }
+        let levels = self.between.get_or_init(|| sub_segments(route, info));
+        let level = &levels[(first ^ last).ilog2() as usize];
+        Some(level[first].concat(&level[last], info))
+    }
+}
+
+/// Segments that any run of patients in `route` is the concatenation of two of (a disjoint sparse
+/// table). Level `l` cuts the route into blocks of `2 << l` positions. In the first half of a block
+/// it holds the segment from each position to the middle, in the second half the segment from the
+/// middle to each position. A run from `first` to `last` joins its two parts on the level of the
+/// highest bit where `first` and `last` differ.
+fn sub_segments(route: &[i32], info: &Info) -> Vec<Vec<Segment>> {
+    let patients: Vec<Segment> = route.iter().map(|p| Segment::patient(*p, info)).collect();
+    let mut levels = Vec::new();
+    let mut half = 1;
+    while half < route.len() {
+        let mut level = patients.clone();
+        for middle in (half..route.len()).step_by(2 * half) {
+            for i in (middle - half..middle - 1).rev() {
+                level[i] = patients[i].concat(&level[i + 1], info);
+            }
+            for i in middle + 1..(middle + half).min(route.len()) {
+                level[i] = level[i - 1].concat(&patients[i], info);
+            }
+        }
+        levels.push(level);
+        half *= 2;
+    }
+    levels
+}
@@ -0,0 +1,250 @@
+use rand::seq::{IndexedRandom, SliceRandom};
+use rand::Rng;
+
+use crate::genetic::evaluate::Segment;
+use crate::genetic::random::GaRng;
+use crate::genetic::route_cache::RouteCache;
+use crate::structs::config::{Config, Insertion, Removal};
+use crate::structs::io::Info;
+use crate::structs::nurse::Nurse;
+
+/// Remove between `ruin_min_removed` and `ruin_max_removed` patients with a random heuristic of
+/// `ruin_removals`, then insert them again with a random heuristic of `ruin_insertions`
+/// (Schrimpf et al. 2000, Ropke & Pisinger 2006)
+pub fn ruin_and_recreate(nurses: &mut [Nurse], rng: &mut GaRng, info: &Info, config: &Config) {
+    let (Some(&removal), Some(&insertion)) = (config.ruin_removals.choose(rng), config.ruin_insertions.choose(rng)) else {
+        return;
+    };
+    let nbr_patients: usize = nurses.iter().map(|nurse| nurse.route.len()).sum();
+    let max_removed = config.ruin_max_removed.min(nbr_patients);
+    if max_removed == 0 {
+        return;
+    }
+    let count = rng.random_range(config.ruin_min_removed.clamp(1, max_removed)..=max_removed);
+
+    let removed = ruin(nurses, removal, count, rng, info, config);
+    recreate(nurses, removed, insertion, rng, info, config);
+}
+
+/// Take `count` patients out of the routes, see `Removal`, or at least `count` for whole routes.
+/// Returns the removed patients, none if the routes are empty
+pub fn ruin(nurses: &mut [Nurse], removal: Removal, count: usize, rng: &mut GaRng, info: &Info, config: &Config) -> Vec<i32> {
+    if nurses.iter().all(|nurse| nurse.route.is_empty()) {
+        return Vec::new();
+    }
+    match removal {
+        Removal::Random => random_removal(nurses, count, rng),
+        Removal::Worst => worst_removal(nurses, count, rng, info, config),
+        Removal::Shaw => shaw_removal(nurses, count, rng, info, config),
+        Removal::Route => route_removal(nurses, count, rng),
+        Removal::String => string_removal(nurses, count, rng, info, config),
+    }
+}
+
+/// Insert every patient again, see `Insertion`
+pub fn recreate(nurses: &mut [Nurse], patients: Vec<i32>, insertion: Insertion, rng: &mut GaRng, info: &Info, config: &Config) {
+    match insertion {
+        Insertion::Greedy => regret_insertion(nurses, patients, 1, info, config),
+        Insertion::Regret => regret_insertion(nurses, patients, config.regret_k.max(2), info, config),
+        Insertion::Blink => blink_insertion(nurses, patients, rng, info, config),
+    }
+}
+
+fn remove_patients(nurses: &mut [Nurse], patients: &[i32]) {
+    for nurse in nurses.iter_mut() {
+        nurse.route.retain(|p| !patients.contains(p));
+    }
+}
+
+fn random_removal(nurses: &mut [Nurse], count: usize, rng: &mut GaRng) -> Vec<i32> {
+    let mut patients: Vec<i32> = nurses.iter().flat_map(|nurse| nurse.route.iter().copied()).collect();
+    patients.shuffle(rng);
+    patients.truncate(count);
+    remove_patients(nurses, &patients);
+    patients
+}
+
+/// Index into a list sorted best first, biased towards the front by `randomness` (`y^p` of
+/// Ropke & Pisinger). Higher randomness picks the front more often
+fn biased_index(len: usize, randomness: f32, rng: &mut GaRng) -> usize {
+    let y: f32 = rng.random();
+    ((y.powf(randomness.max(1.)) * len as f32) as usize).min(len - 1)
+}
+
+/// Remove patients whose removal saves the most, one at a time
+fn worst_removal(nurses: &mut [Nurse], count: usize, rng: &mut GaRng, info: &Info, config: &Config) -> Vec<i32> {
+    let mut caches: Vec<RouteCache> = nurses.iter().map(|nurse| RouteCache::new(&nurse.route, info, config)).collect();
+    let mut removed = Vec::with_capacity(count);
+    while removed.len() < count {
+        let mut savings: Vec<(f32, usize, usize)> = Vec::new();
+        for (n, (nurse, cache)) in nurses.iter().zip(&caches).enumerate() {
+            for i in 0..nurse.route.len() {
+                savings.push((cache.remove_delta(&nurse.route, i, i + 1, info, config), n, i));
+            }
+        }
+        if savings.is_empty() {
+            break;
+        }
+        savings.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let (_, n, i) = savings[biased_index(savings.len(), config.worst_removal_randomness, rng)];
+        removed.push(nurses[n].route.remove(i));
+        caches[n] = RouteCache::new(&nurses[n].route, info, config);
+    }
+    removed
+}
+
+/// How alike two patients are, lower is more related: travel time between them and difference in
+/// time window start, each relative to its largest value
+fn relatedness(a: i32, b: i32, info: &Info, config: &Config) -> f32 {
+    let (pa, pb) = (&info.patients[a as usize], &info.patients[b as usize]);
+    let distance = info.travel_times[a as usize + 1][b as usize + 1] / info.travel_times.max().max(f32::EPSILON);
+    let time = (pa.start_time as f32 - pb.start_time as f32).abs() / (info.depot.return_time as f32).max(1.);
+    config.shaw_distance_weight * distance + config.shaw_time_weight * time
+}
+
+/// Remove a random patient, then patients related to one already removed
+fn shaw_removal(nurses: &mut [Nurse], count: usize, rng: &mut GaRng, info: &Info, config: &Config) -> Vec<i32> {
+    let mut left: Vec<i32> = nurses.iter().flat_map(|nurse| nurse.route.iter().copied()).collect();
+
+    let mut removed = vec![left.swap_remove(rng.random_range(0..left.len()))];
+    while removed.len() < count && !left.is_empty() {
+        let reference = *removed.choose(rng).unwrap();
+        left.sort_by(|a, b| relatedness(reference, *a, info, config).total_cmp(&relatedness(reference, *b, info, config)));
+        removed.push(left.remove(biased_index(left.len(), config.shaw_randomness, rng)));
+    }
+    remove_patients(nurses, &removed);
+    removed
+}
+
+/// Empty random routes until at least `count` patients are removed
+fn route_removal(nurses: &mut [Nurse], count: usize, rng: &mut GaRng) -> Vec<i32> {
+    let mut routes: Vec<usize> = (0..nurses.len()).filter(|n| !nurses[*n].route.is_empty()).collect();
+    routes.shuffle(rng);
+    let mut removed = Vec::new();
+    for n in routes {
+        if removed.len() >= count {
+            break;
+        }
+        removed.append(&mut nurses[n].route);
+    }
+    removed
+}
+
+/// Remove strings of consecutive patients from routes near a random patient (the ruin of SISR,
+/// Christiaens & Vanden Berghe 2020). Strings are at most `sisr_max_string_length` long, and
+/// one string is taken from every route until `count` patients are removed. Routes are visited
+/// in order of the nearest patient in them, as far as `travel_times_sorted` reaches, then the
+/// other routes at random. If one string per route is not enough, the rest are removed at random.
+fn string_removal(nurses: &mut [Nurse], count: usize, rng: &mut GaRng, info: &Info, config: &Config) -> Vec<i32> {
+    let mut route_of = vec![None; info.patients.len()];
+    for (n, nurse) in nurses.iter().enumerate() {
+        nurse.route.iter().for_each(|p| route_of[*p as usize] = Some(n));
+    }
+    let patients: Vec<i32> = nurses.iter().flat_map(|nurse| nurse.route.iter().copied()).collect();
+    let seed = *patients.choose(rng).unwrap();
+
+    // The seed, then its nearest patients, then a random patient of every route
+    let near = info.travel_times_sorted[seed as usize + 1].iter()
+        .filter(|node| **node != 0)
+        .map(|node| *node as i32 - 1);
+    let mut others: Vec<i32> = nurses.iter().filter_map(|nurse| nurse.route.choose(rng).copied()).collect();
+    others.shuffle(rng);
+    let mut ruined_routes = Vec::new();
+    let mut removed = Vec::new();
+    for p in std::iter::once(seed).chain(near).chain(others) {
+        if removed.len() >= count {
+            break;
+        }
+        let Some(n) = route_of[p as usize] else { continue };
+        if ruined_routes.contains(&n) {
+            continue;
+        }
+        ruined_routes.push(n);
+
+        let route = &mut nurses[n].route;
+        let position = route.iter().position(|q| *q == p).unwrap();
+        let max_length = config.sisr_max_string_length.max(1).min(route.len()).min(count - removed.len());
+        let length = rng.random_range(1..=max_length);
+        // A string of `length` patients that contains `p`
+        let first = rng.random_range(position.saturating_sub(length - 1)..=position.min(route.len() - length));
+        removed.extend(route.drain(first..first + length));
+    }
+    if removed.len() < count {
+        removed.extend(random_removal(nurses, count - removed.len(), rng));
+    }
+    removed
+}
+
+/// Best insertion of `patient` into one route: change in cost and position
+fn best_insertion(route: &[i32], cache: &RouteCache, patient: i32, info: &Info, config: &Config) -> (f32, usize) {
+    let segment = Segment::patient(patient, info);
+    (0..=route.len())
+        .map(|position| (cache.splice_segment_delta(route, position, position, &[patient], Some(&segment), info, config), position))
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+        .unwrap()
+}
+
+/// Insert the patient with the highest regret first: the sum over its `k - 1` next best routes of
+/// how much more inserting it there costs than in its best route. With `k == 1` it is greedy
+/// insertion, the cheapest insertion of any patient first. Ties go to the cheapest insertion.
+fn regret_insertion(nurses: &mut [Nurse], mut patients: Vec<i32>, k: usize, info: &Info, config: &Config) {
+    let mut caches: Vec<RouteCache> = nurses.iter().map(|nurse| RouteCache::new(&nurse.route, info, config)).collect();
+    // Best insertion of every patient left into every route, updated for the changed route only
+    let mut insertions: Vec<Vec<(f32, usize)>> = patients.iter()
+        .map(|p| nurses.iter().zip(&caches).map(|(nurse, cache)| best_insertion(&nurse.route, cache, *p, info, config)).collect())
+        .collect();
+
+    while !patients.is_empty() {
+        let mut chosen = (0, f32::NEG_INFINITY, f32::INFINITY);
+        for (i, routes) in insertions.iter().enumerate() {
+            let mut costs: Vec<f32> = routes.iter().map(|(cost, _)| *cost).collect();
+            costs.sort_by(|a, b| a.total_cmp(b));
+            let regret: f32 = costs.iter().skip(1).take(k - 1).map(|cost| cost - costs[0]).sum();
+            if regret > chosen.1 || (regret == chosen.1 && costs[0] < chosen.2) {
+                chosen = (i, regret, costs[0]);
+            }
+        }
+
+        let (i, _, _) = chosen;
+        let patient = patients.swap_remove(i);
+        let routes = insertions.swap_remove(i);
+        let (n, (_, position)) = routes.into_iter().enumerate().min_by(|a, b| a.1.0.total_cmp(&b.1.0)).unwrap();
+        nurses[n].route.insert(position, patient);
+        caches[n] = RouteCache::new(&nurses[n].route, info, config);
+        for (p, routes) in patients.iter().zip(insertions.iter_mut()) {
+            routes[n] = best_insertion(&nurses[n].route, &caches[n], *p, info, config);
+        }
+    }
+}
+
+/// Insert the patients one at a time, sorted at random, by time window start or by distance to the
+/// depot, at their best position. Every position is skipped with probability `blink_rate`
+/// (the recreate of SISR)
+fn blink_insertion(nurses: &mut [Nurse], mut patients: Vec<i32>, rng: &mut GaRng, info: &Info, config: &Config) {
+    match rng.random_range(0..3) {
+        0 => patients.shuffle(rng),
+        1 => patients.sort_by_key(|p| info.patients[*p as usize].start_time),
+        _ => patients.sort_by(|a, b| info.travel_times[0][*a as usize + 1].total_cmp(&info.travel_times[0][*b as usize + 1])),
+    }
+
+    let mut caches: Vec<RouteCache> = nurses.iter().map(|nurse| RouteCache::new(&nurse.route, info, config)).collect();
+    for patient in patients {
+        let segment = Segment::patient(patient, info);
+        let mut best: Option<(f32, usize, usize)> = None;
+        for (n, (nurse, cache)) in nurses.iter().zip(&caches).enumerate() {
+            for position in 0..=nurse.route.len() {
+                // Never skip before a position is found, so every patient gets a place
+                if best.is_some() && rng.random_bool(config.blink_rate.clamp(0., 1.) as f64) {
+                    continue;
+                }
+                let delta = cache.splice_segment_delta(&nurse.route, position, position, &[patient], Some(&segment), info, config);
+                if best.is_none_or(|(best_delta, _, _)| delta < best_delta) {
+                    best = Some((delta, n, position));
+                }
+            }
+        }
+        let (_, n, position) = best.unwrap();
+        nurses[n].route.insert(position, patient);
+        caches[n] = RouteCache::new(&nurses[n].route, info, config);
+    }
+}
@@ -1,26 +1,22 @@
 use crate::genetic::initialize_population::init_population;
-use crate::structs::config::{Config, ScrambleFN};
+use crate::genetic::operators::RestartStrategy;
+use crate::genetic::random::GaRng;
+use crate::structs::config::Config;
 use crate::structs::io::Info;
 use crate::structs::nurse::Individual;
 
-type ScrambleFNType = fn(&mut Vec<Individual>, &Info, &Config);
-
-pub fn scramble_population(population: &mut Vec<Individual>, info: &Info, config: &Config) {
+pub fn scramble_population(population: &mut Vec<Individual>, rng: &mut GaRng, info: &Info, config: &Config, restart: &dyn RestartStrategy) {
     println!("Scramble population:");
-    let scramble: ScrambleFNType = match config.scramble_fn {
-        ScrambleFN::Delete => delete,
-        ScrambleFN::Keep => keep,
-    };
-    scramble(population, &info, &config);
+    restart.restart(population, rng, info, config);
 }
 
-fn delete(individuals: &mut Vec<Individual>, info: &Info, config: &Config) {
-    let new_population = init_population(&info, &config);
+pub fn delete(individuals: &mut Vec<Individual>, rng: &mut GaRng, info: &Info, config: &Config) {
+    let new_population = init_population(info, config, rng);
     *individuals = new_population;
 }
-fn keep(individuals: &mut Vec<Individual>, info: &Info, config: &Config) {
+pub fn keep(individuals: &mut Vec<Individual>, rng: &mut GaRng, info: &Info, config: &Config) {
     let best_individual = individuals.last().unwrap().clone();
-    let new_population = init_population(&info, &config);
+    let new_population = init_population(info, config, rng);
     *individuals = new_population;
     individuals.remove(individuals.len()-1);
     individuals.push(best_individual);
@@ -0,0 +1,126 @@
+use crate::genetic::evaluate::{fitness_population, penalized_cost, penalties, travel_time, Segment};
+use crate::structs::config::Config;
+use crate::structs::io::Info;
+use crate::structs::nurse::{Individual, Nurse};
+
+/// Every patient of an individual in one sequence, the routes in nurse order
+pub fn giant_tour(individual: &Individual) -> Vec<i32> {
+    individual.nurses.iter().flat_map(|nurse| nurse.route.iter().copied()).collect()
+}
+
+/// Individual with the routes `split` makes out of `tour`, evaluated with the configured model
+pub fn from_giant_tour(tour: &[i32], info: &Info, config: &Config) -> Individual {
+    let mut individual = vec![Individual { nurses: split(tour, info, config), fitness: 0., feasible: false }];
+    fitness_population(&mut individual, info, config);
+    individual.pop().unwrap()
+}
+
+/// Cut a giant tour into at most `nbr_nurses` routes of consecutive patients with the lowest
+/// total cost (Prins 2004, with the bounded fleet of Vidal 2016). The cost of a route is its travel
+/// time plus the `TimeWarp` penalties for lateness and excess demand, so every tour has a split.
+/// This is also the cost used under the `Legacy` model, whose penalties do not add up route by route.
+/// Returns `nbr_nurses` nurses, unused ones empty.
+pub fn split(tour: &[i32], info: &Info, config: &Config) -> Vec<Nurse> {
+    let max_routes = (info.nbr_nurses as usize).min(tour.len());
+    let cuts = capacity_cuts(tour, max_routes, info);
+    // Any split bounds the search, this one is quick to build
+    let cost = cuts.windows(2)
+        .map(|route| penalized_cost(&Segment::of_route(&tour[route[0]..route[1]], info), info, config))
+        .sum();
+
+    let cuts = split_cuts(tour, max_routes, cost, info, config).unwrap_or(cuts);
+
+    let mut nurses = vec![Nurse::new(); info.nbr_nurses as usize];
+    for (nurse, route) in nurses.iter_mut().zip(cuts.windows(2)) {
+        nurse.route = tour[route[0]..route[1]].to_vec();
+    }
+    nurses
+}
+
+/// Start of every route and the end of the tour for the cheapest split into at most `max_routes`
+/// routes, or `None` if no split costs less than `bound`
+fn split_cuts(tour: &[i32], max_routes: usize, bound: f32, info: &Info, config: &Config) -> Option<Vec<usize>> {
+    let n = tour.len();
+    if n == 0 || max_routes == 0 {
+        return None;
+    }
+
+    // costs[k][j] is the lowest cost of serving the first j patients with k routes
+    let mut costs = vec![vec![f32::INFINITY; n + 1]; max_routes + 1];
+    let mut previous_cut = vec![vec![0; n + 1]; max_routes + 1];
+    costs[0][0] = 0.;
+    let depot = Segment::depot(info);
+    // Every patient is left for another patient or the depot, so the patients from position j on
+    // add at least rest[j] of travel. That and the penalties only grow as a route gets longer, so
+    // a route can stop growing once they reach the cost of a known split.
+    let mut rest = vec![0.; n + 1];
+    for j in (0..n).rev() {
+        rest[j] = rest[j + 1] + shortest_departure(tour[j], info);
+    }
+    let known_cost = bound;
+    let mut bound = bound;
+    // Serving the first i patients at no lower cost with more routes leaves fewer for the rest
+    let mut fewest_routes_cost = vec![f32::INFINITY; n + 1];
+
+    for k in 0..max_routes {
+        for i in k..n {
+            if costs[k][i] + rest[i] >= bound || costs[k][i] >= fewest_routes_cost[i] {
+                continue;
+            }
+            fewest_routes_cost[i] = costs[k][i];
+            let mut route = depot;
+            for j in i..n {
+                route = route.concat(&Segment::patient(tour[j], info), info);
+                if costs[k][i] + route.distance + penalties(&route, info, config) + rest[j] >= bound {
+                    break;
+                }
+                let cost = costs[k][i] + penalized_cost(&route.concat(&depot, info), info, config);
+                if cost < costs[k + 1][j + 1] {
+                    costs[k + 1][j + 1] = cost;
+                    previous_cut[k + 1][j + 1] = i;
+                }
+            }
+        }
+        bound = bound.min(costs[k + 1][n]);
+    }
+
+    let best_routes = (1..=max_routes).min_by(|a, b| costs[*a][n].total_cmp(&costs[*b][n]))?;
+    if costs[best_routes][n] >= known_cost {
+        return None;
+    }
+    let mut cuts = vec![n];
+    let mut end = n;
+    for k in (1..=best_routes).rev() {
+        end = previous_cut[k][end];
+        cuts.push(end);
+    }
+    cuts.reverse();
+    Some(cuts)
+}
+
+/// Cuts that start a new route whenever the next patient would exceed the capacity, with the last
+/// of the `max_routes` routes taking whatever is left
+fn capacity_cuts(tour: &[i32], max_routes: usize, info: &Info) -> Vec<usize> {
+    let mut cuts = vec![0];
+    let mut load = 0;
+    for (position, &patient) in tour.iter().enumerate() {
+        let demand = info.patients[patient as usize].demand;
+        if load + demand > info.capacity_nurse && load > 0 && cuts.len() < max_routes {
+            cuts.push(position);
+            load = 0;
+        }
+        load += demand;
+    }
+    cuts.push(tour.len());
+    cuts
+}
+
+/// Shortest travel time from the zero-indexed `patient` to the next patient or back to the depot
+fn shortest_departure(patient: i32, info: &Info) -> f32 {
+    let node = patient as usize + 1;
+    let back = travel_time(node, 0, info);
+    match info.travel_times_sorted[node].iter().find(|&&next| next != 0) {
+        Some(&next) => back.min(travel_time(node, next, info)),
+        None => back,
+    }
+}
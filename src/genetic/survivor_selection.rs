@@ -1,93 +1,34 @@
-use rand::{rng, Rng};
-use crate::structs::config::{Config, SurvivorSelectionFN};
+use rand::Rng;
+use crate::genetic::diversity::Neighbours;
+use crate::genetic::operators::SurvivorSelection;
+use crate::genetic::random::GaRng;
+use crate::structs::config::Config;
 use crate::structs::nurse::Individual;
 
 pub fn survivor_selection(
-    population: &mut Vec<Individual>,
-    parent_indices: &Vec<usize>,
-    children: &mut Vec<Individual>,
-    config: &Config
+    population: &mut [Individual],
+    parent_indices: &[usize],
+    children: &[Individual],
+    rng: &mut GaRng,
+    config: &Config,
+    selection: &dyn SurvivorSelection,
 ) {
-    match config.survivor_selection_fn {
-        SurvivorSelectionFN::CrowdingOld => crowding(population, &children, &config),
-        SurvivorSelectionFN::Crowding => crowding_optimized(population, &children, &config, &parent_indices),
-    };
-}
-
-
-// With the right compile flags, this is faster than Hashmap version
-pub fn _similarity(
-    individual1: &Individual,
-    individual2: &Individual,
-    pop_size: usize
-) -> f32 {
-    let mut similarity = 0;
-    for nurse1 in &individual1.nurses {
-        if nurse1.route.len() < 2 {
-            continue;
-        }
-        // Check edges
-        for i in 0..nurse1.route.len()-1 {
-            let edge1 = (nurse1.route[i], nurse1.route[i+1]);
-            // Check if other individual contains these edges
-            for nurse2 in &individual2.nurses {
-                if nurse2.route.len() < 2 {
-                    continue;
-                }
-                for j in 0..nurse2.route.len()-1 {
-                    let edge2 = (nurse2.route[j], nurse2.route[j+1]);
-                    if edge1 == edge2 {
-                        similarity += 1;
-                    }
-                }
-            }
-        }
-    }
-
-    similarity as f32 / pop_size as f32
+    selection.select(population, parent_indices, children, rng, config);
 }
 
-pub fn similarity(
-    individual1: &Individual,
-    individual2: &Individual,
-    pop_size: usize
-) -> f32 {
-    let mut similarity = 0;
-    for nurse1 in &individual1.nurses {
-        if nurse1.route.len() < 2 {
-            continue;
-        }
-        // Check edges
-        for i in 0..nurse1.route.len()-1 {
-            let edge1 = (nurse1.route[i], nurse1.route[i+1]);
-            // Check if other individual contains these edges
-            for nurse2 in &individual2.nurses {
-                if nurse2.route.len() < 2 {
-                    continue;
-                }
-                for j in 0..nurse2.route.len()-1 {
-                    let edge2 = (nurse2.route[j], nurse2.route[j+1]);
-                    if edge1 == edge2 {
-                        similarity += 1;
-                    }
-                }
-            }
-        }
-    }
-
-    similarity as f32 / pop_size as f32
-}
 
-pub fn crowding(population: &mut Vec<Individual>, children: &Vec<Individual>, config: &Config) {
-    let mut rng = rand::rng();
+/// Every child competes with the closest individual in the population by broken-pairs distance
+pub fn crowding(population: &mut [Individual], children: &[Individual], rng: &mut GaRng, config: &Config) {
+    let mut neighbours: Vec<Neighbours> = population.iter().map(Neighbours::new).collect();
     for child in children {
+        let child_neighbours = Neighbours::new(child);
         let mut closest_index = 0;
-        let mut closest_similarity_score = 0.0;
+        let mut closest_distance = f32::INFINITY;
 
-        for (i, individual) in population.iter().enumerate() {
-            let similarity_score = similarity(&child, individual, population.len());
-            if similarity_score > closest_similarity_score {
-                closest_similarity_score = similarity_score;
+        for (i, individual_neighbours) in neighbours.iter().enumerate() {
+            let distance = child_neighbours.distance(individual_neighbours);
+            if distance < closest_distance {
+                closest_distance = distance;
                 closest_index = i;
             }
         }
@@ -104,16 +45,19 @@ pub fn crowding(population: &mut Vec<Individual>, children: &Vec<Individual>, co
 
         if rng.random_range(0.0..1.) < probability {
             population[closest_index] = child.clone();
+            neighbours[closest_index] = child_neighbours;
         }
     }
 }
 
+/// Deterministic crowding: each child competes with the closer of its two parents.
 /// Single-threaded
 pub fn crowding_optimized(
-    population: &mut Vec<Individual>,
-    children: &Vec<Individual>,
+    population: &mut [Individual],
+    children: &[Individual],
+    rng: &mut GaRng,
     config: &Config,
-    parent_indices: &Vec<usize>,
+    parent_indices: &[usize],
 ) {
     parent_indices.chunks_exact(2).zip(children.chunks_exact(2))
         .for_each(|a| {
@@ -122,20 +66,22 @@ pub fn crowding_optimized(
             let parent1_idx = a.0[0];
             let parent2_idx = a.0[1];
 
-            if similarity(child1, &population[parent1_idx], population.len()) +
-                similarity(child2, &population[parent2_idx], population.len()) <
-                similarity(child1, &population[parent2_idx], population.len()) +
-                    similarity(child2, &population[parent1_idx], population.len()) {
-                compete(population, child1, parent1_idx, &config);
-                compete(population, child2, parent2_idx, &config);
+            let (child1_neighbours, child2_neighbours) = (Neighbours::new(child1), Neighbours::new(child2));
+            let parent1_neighbours = Neighbours::new(&population[parent1_idx]);
+            let parent2_neighbours = Neighbours::new(&population[parent2_idx]);
+
+            if child1_neighbours.distance(&parent1_neighbours) + child2_neighbours.distance(&parent2_neighbours) <=
+                child1_neighbours.distance(&parent2_neighbours) + child2_neighbours.distance(&parent1_neighbours) {
+                compete(population, child1, parent1_idx, rng, config);
+                compete(population, child2, parent2_idx, rng, config);
             } else {
-                compete(population, child1, parent2_idx, &config);
-                compete(population, child2, parent1_idx, &config);
+                compete(population, child1, parent2_idx, rng, config);
+                compete(population, child2, parent1_idx, rng, config);
             }
         });
 }
 
-fn compete(population: &mut Vec<Individual>, child: &Individual, parent_idx: usize, config: &Config) {
+fn compete(population: &mut [Individual], child: &Individual, parent_idx: usize, rng: &mut GaRng, config: &Config) {
     let parent = &population[parent_idx];
     let child_fitness = child.fitness;
     let parent_fitness = parent.fitness;
@@ -148,7 +94,7 @@ fn compete(population: &mut Vec<Individual>, child: &Individual, parent_idx: usi
         probability = (config.scaling_factor * child_fitness) / (config.scaling_factor * child_fitness + parent_fitness);
     }
 
-    if rng().random_range(0.0..1.) < probability {
+    if rng.random_range(0.0..1.) < probability {
         population[parent_idx] = child.clone();
     }
 }
\ No newline at end of file
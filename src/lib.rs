@@ -0,0 +1,9 @@
+pub mod cli;
+pub mod genetic;
+pub mod structs;
+pub mod util;
+mod solver;
+#[cfg(test)]
+mod test;
+
+pub use solver::{SolveResult, Solver, SolverBuilder};
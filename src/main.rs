@@ -1,21 +1,10 @@
-extern crate core;
+use clap::Parser;
 
-mod genetic;
-mod structs;
-mod util;
-mod test;
-
-use genetic::genetic_algo;
-
-use crate::structs::config::Config;
-use crate::util::plot::plot_best_individual;
+use memetic_ga::cli::{self, Cli};
 
 fn main() {
-    let config = Config::new("./config/config.yaml");
-    if config.print_and_graph {
-        println!("Starting plotting");
-        plot_best_individual();
-        println!("Finished plotting");
+    if let Err(e) = cli::run(Cli::parse()) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
     }
-    genetic_algo::init(config);
 }
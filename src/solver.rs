@@ -0,0 +1,126 @@
+use std::error::Error;
+use std::sync::Arc;
+use std::thread::available_parallelism;
+
+use crate::genetic::genetic_algo::{self, ImprovementFN};
+use crate::genetic::island::start_islands;
+use crate::genetic::operators::{OperatorRegistry, Operators};
+use crate::structs::config::Config;
+use crate::structs::io::Info;
+use crate::structs::nurse::Individual;
+use crate::structs::stats::RunStats;
+
+/// Result of a solver run
+#[derive(Debug, Clone)]
+pub struct SolveResult {
+    /// Best individual found. Feasible individuals are preferred over infeasible ones
+    pub best: Individual,
+    pub stats: RunStats,
+}
+
+/// Runs the memetic genetic algorithm on an in-memory instance.
+///
+/// ```no_run
+/// use memetic_ga::Solver;
+/// use memetic_ga::structs::config::Config;
+/// use memetic_ga::structs::io::read_instance;
+///
+/// let config = Config::new("config/config.yaml");
+/// let info = read_instance(&config).unwrap();
+/// let result = Solver::builder(info, config)
+///     .on_improvement(|individual| println!("New best: {}", individual.fitness))
+///     .build()
+///     .unwrap()
+///     .solve();
+/// println!("{} after {} generations", result.best.fitness, result.stats.generations);
+/// ```
+pub struct Solver {
+    info: Info,
+    config: Config,
+    operators: Operators,
+    num_islands: usize,
+    on_improvement: Option<Arc<ImprovementFN>>,
+}
+
+pub struct SolverBuilder {
+    info: Info,
+    config: Config,
+    registry: OperatorRegistry,
+    num_islands: Option<usize>,
+    on_improvement: Option<Arc<ImprovementFN>>,
+}
+
+impl Solver {
+    /// Solver with the built-in operators
+    pub fn new(info: Info, config: Config) -> Result<Self, Box<dyn Error>> {
+        Solver::builder(info, config).build()
+    }
+
+    pub fn builder(info: Info, config: Config) -> SolverBuilder {
+        SolverBuilder {
+            info,
+            config,
+            registry: OperatorRegistry::default(),
+            num_islands: None,
+            on_improvement: None,
+        }
+    }
+
+    pub fn info(&self) -> &Info {
+        &self.info
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Run until `n_generations` or `run_time` is reached
+    pub fn solve(&self) -> SolveResult {
+        let (best, stats) = if self.config.use_islands {
+            start_islands(&self.info, &self.config, &self.operators, self.num_islands, self.on_improvement.as_deref())
+        } else {
+            genetic_algo::start(&self.info, &self.config, &self.operators, self.on_improvement.as_deref())
+        };
+        SolveResult { best, stats }
+    }
+}
+
+impl SolverBuilder {
+    /// Operators that the config can refer to by name. Defaults to `OperatorRegistry::default()`
+    pub fn registry(mut self, registry: OperatorRegistry) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    /// Number of islands when `use_islands` is set. Defaults to one less than the available threads
+    pub fn islands(mut self, num_islands: usize) -> Self {
+        self.num_islands = Some(num_islands.max(1));
+        self
+    }
+
+    /// Called from the solver threads every time a population finds a new best feasible individual
+    pub fn on_improvement<F>(mut self, on_improvement: F) -> Self
+    where
+        F: Fn(&Individual) + Send + Sync + 'static,
+    {
+        self.on_improvement = Some(Arc::new(on_improvement));
+        self
+    }
+
+    /// Fails if the config is invalid, see `Config::validate`, or names an operator that is not
+    /// in the registry
+    pub fn build(self) -> Result<Solver, Box<dyn Error>> {
+        self.config.validate()?;
+        let operators = self.registry.resolve(&self.config)?;
+        let num_islands = self.num_islands.unwrap_or_else(|| {
+            available_parallelism().map(|n| n.get().saturating_sub(1)).unwrap_or(1).max(1)
+        });
+        Ok(Solver {
+            info: self.info,
+            config: self.config,
+            operators,
+            num_islands,
+            on_improvement: self.on_improvement,
+        })
+    }
+}
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
-use serde_yaml;
+use serde_yaml::Value;
+use std::error::Error;
 use std::fs;
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -8,31 +9,139 @@ pub enum InitialPopType {
     File,
 }
 
-#[derive(Debug, Deserialize, Clone)]
-pub enum ParentSelectionFN {
+/// How infeasible routes are punished in `fitness_nurse`
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Default)]
+pub enum PenaltyModel {
+    /// `travel_time * fitness_punishment_factor` per late patient, and a flat 1000
+    /// for too much demand or a late return to the depot
+    #[default]
+    Legacy,
+    /// `time_warp_penalty` per unit of lateness and `load_penalty` per unit of demand over the
+    /// capacity, so a route is punished by how infeasible it is
+    TimeWarp,
+}
+
+/// Which improving move `educate` applies
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Default)]
+pub enum Improvement {
+    /// The first one found
+    #[default]
+    First,
+    /// The best one in the neighbourhood
+    Best,
+}
+
+/// Moves tried by `educate`
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub enum Neighbourhood {
+    /// Move one patient to another position, in any route
+    Relocate,
+    /// Exchange two patients, in any routes
+    Swap,
+    /// Reverse part of a route
+    TwoOpt,
+    /// Exchange the ends of two routes
+    TwoOptStar,
+    /// Move two or three consecutive patients within their route
+    OrOpt,
+    /// Exchange up to three consecutive patients between two routes
+    CrossExchange,
+}
+
+/// How `ruin_and_recreate` picks the patients to remove
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub enum Removal {
+    Random,
+    /// Patients whose removal saves the most
+    Worst,
+    /// Patients close in distance and time window start to each other
+    Shaw,
+    /// Whole routes
+    Route,
+    /// Strings of consecutive patients from routes near each other
+    String,
+}
+
+/// How `ruin_and_recreate` inserts the removed patients again
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub enum Insertion {
+    /// Cheapest insertion of any patient first
+    Greedy,
+    /// Patient that loses the most by not getting its best route first, over `regret_k` routes
+    Regret,
+    /// One patient at a time at its best position, skipping positions with probability `blink_rate`
+    Blink,
+}
+
+/// Config value naming an operator in the `OperatorRegistry`. Built-in operators get
+/// their own variant, any other name is kept as `Custom` and looked up at start-up.
+macro_rules! operator_enum {
+    ($name:ident { $($variant:ident),* $(,)? }) => {
+        #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+        #[serde(from = "String", into = "String")]
+        pub enum $name {
+            $($variant,)*
+            Custom(String),
+        }
+
+        impl $name {
+            pub fn name(&self) -> &str {
+                match self {
+                    $($name::$variant => stringify!($variant),)*
+                    $name::Custom(name) => name,
+                }
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(name: String) -> Self {
+                match name.as_str() {
+                    $(stringify!($variant) => $name::$variant,)*
+                    _ => $name::Custom(name),
+                }
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(operator: $name) -> Self {
+                operator.name().to_string()
+            }
+        }
+    };
+}
+
+operator_enum!(ParentSelectionFN {
     LinearRanking,
     Probabilistic,
     Tournament,
-}
+});
 
-#[derive(Deserialize, Serialize, Debug, Clone)]
-pub enum CrossoverFN {
+operator_enum!(CrossoverFN {
     Visma,
-}
+    Order,
+    EdgeRecombination,
+    RouteBased,
+    Srex,
+});
 
-#[derive(Deserialize, Serialize, Debug, Clone)]
-pub enum SurvivorSelectionFN {
+operator_enum!(SurvivorSelectionFN {
     CrowdingOld,
     Crowding,
-}
+});
 
-#[derive(Deserialize, Serialize, Debug, Clone)]
-pub enum ScrambleFN {
+operator_enum!(ScrambleFN {
     Delete,
     Keep,
+});
+
+/// A mutation from the `OperatorRegistry` and the probability of applying it to a child
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct MutationRate {
+    pub name: String,
+    pub rate: f32,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Config  {
     pub use_islands: bool,
     pub island_share_frequency: i32,
@@ -41,17 +150,85 @@ pub struct Config  {
     pub log_frequency: i32,
 
     pub file_name: String,
+    /// Seed for the random number generator. Runs with the same seed and no
+    /// `run_time` limit give the same result
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Instance file to read instead of `train/<file_name>.json`
+    #[serde(default)]
+    pub instance_path: Option<String>,
+    /// Folder where the best individuals are saved, one sub-folder per instance
+    #[serde(default = "default_output_dir")]
+    pub output_dir: String,
+    /// Improve every route with 2-opt and Or-opt before a solution is saved
+    #[serde(default)]
+    pub polish_before_saving: bool,
     pub population_size: i32,
     pub n_generations: i32,
     pub n_elitism: i32,
     pub n_stagnations: i32,
     pub crossover_rate: f32,
 
+    #[serde(default)]
     pub heuristic_cluster_mutation_rate: f32,
+    #[serde(default)]
     pub heuristic_swap_mutation_rate: f32,
+    #[serde(default)]
     pub heuristic_random_swap_mutation_rate: f32,
+    #[serde(default)]
     pub insert_mutation_rate: f32,
+    #[serde(default)]
     pub large_neighbourhood_mutation_rate: f32,
+    #[serde(default)]
+    pub two_opt_star_mutation_rate: f32,
+    #[serde(default)]
+    pub cross_exchange_mutation_rate: f32,
+    #[serde(default)]
+    pub two_opt_mutation_rate: f32,
+    #[serde(default)]
+    pub or_opt_mutation_rate: f32,
+    #[serde(default)]
+    pub ruin_recreate_mutation_rate: f32,
+    /// Removal heuristics of the ruin and recreate mutation, one is picked at random every time
+    #[serde(default = "default_ruin_removals")]
+    pub ruin_removals: Vec<Removal>,
+    /// Insertion heuristics of the ruin and recreate mutation, one is picked at random every time
+    #[serde(default = "default_ruin_insertions")]
+    pub ruin_insertions: Vec<Insertion>,
+    #[serde(default = "default_ruin_min_removed")]
+    pub ruin_min_removed: usize,
+    #[serde(default = "default_ruin_max_removed")]
+    pub ruin_max_removed: usize,
+    /// How strongly worst removal prefers the largest savings, at least 1 (1 is uniform)
+    #[serde(default = "default_worst_removal_randomness")]
+    pub worst_removal_randomness: f32,
+    /// How strongly Shaw removal prefers the most related patients, at least 1 (1 is uniform)
+    #[serde(default = "default_shaw_randomness")]
+    pub shaw_randomness: f32,
+    /// Weight of travel time in Shaw relatedness
+    #[serde(default = "default_shaw_distance_weight")]
+    pub shaw_distance_weight: f32,
+    /// Weight of the difference in time window start in Shaw relatedness
+    #[serde(default = "default_shaw_time_weight")]
+    pub shaw_time_weight: f32,
+    /// Longest string removed from one route by string removal
+    #[serde(default = "default_sisr_max_string_length")]
+    pub sisr_max_string_length: usize,
+    /// Number of routes regret insertion looks at
+    #[serde(default = "default_regret_k")]
+    pub regret_k: usize,
+    /// Probability of skipping a position in blink insertion
+    #[serde(default = "default_blink_rate")]
+    pub blink_rate: f32,
+    /// Longest segment exchanged by the cross-exchange mutation and neighbourhood
+    #[serde(default = "default_cross_exchange_max_length")]
+    pub cross_exchange_max_length: usize,
+    /// Let cross-exchange also insert the segments reversed
+    #[serde(default)]
+    pub cross_exchange_reverse: bool,
+    /// Mutations by registry name. When empty the `*_mutation_rate` fields are used
+    #[serde(default)]
+    pub mutations: Vec<MutationRate>,
 
     pub init_population_fn: InitialPopType,
     pub parent_selection_fn: ParentSelectionFN,
@@ -62,14 +239,246 @@ pub struct Config  {
     pub scaling_factor: f32,
     pub n_parents_scaling: f32,
     pub fitness_punishment_factor: f32,
+    #[serde(default)]
+    pub penalty_model: PenaltyModel,
+    /// Penalty per unit of time warp with the `TimeWarp` penalty model
+    #[serde(default = "default_time_warp_penalty")]
+    pub time_warp_penalty: f32,
+    /// Penalty per unit of demand over the nurse capacity with the `TimeWarp` penalty model
+    #[serde(default = "default_load_penalty")]
+    pub load_penalty: f32,
+    /// Adapt the penalty weights during the run. With the `Legacy` model only
+    /// `fitness_punishment_factor` is adapted
+    #[serde(default)]
+    pub adaptive_penalties: bool,
+    /// Fraction of children that should meet the time windows, and the capacity
+    #[serde(default = "default_target_feasible_ratio")]
+    pub target_feasible_ratio: f32,
+    /// Generations between adjustments of the penalty weights
+    #[serde(default = "default_penalty_adjust_frequency")]
+    pub penalty_adjust_frequency: i32,
+
+    /// Keep feasible and infeasible individuals in separate subpopulations. Replaces
+    /// survivor selection and elitism with keeping the best distinct individuals of each
+    #[serde(default)]
+    pub subpopulations: bool,
+    #[serde(default = "default_subpopulation_size")]
+    pub feasible_population_size: i32,
+    #[serde(default = "default_subpopulation_size")]
+    pub infeasible_population_size: i32,
+
+    /// Select parents, and trim subpopulations, on biased fitness: the rank by fitness
+    /// plus the rank by diversity contribution
+    #[serde(default)]
+    pub biased_fitness: bool,
+    /// Number of closest individuals the diversity contribution is averaged over
+    #[serde(default = "default_diversity_neighbours")]
+    pub diversity_neighbours: i32,
+    /// Roughly how many of the best individuals by fitness are kept no matter their diversity
+    #[serde(default = "default_biased_fitness_elite")]
+    pub biased_fitness_elite: i32,
+
+    /// Run a local search on every child after mutation
+    #[serde(default)]
+    pub education: bool,
+    #[serde(default)]
+    pub education_improvement: Improvement,
+    /// Neighbourhoods in the order they are tried
+    #[serde(default = "default_education_neighbourhoods")]
+    pub education_neighbourhoods: Vec<Neighbourhood>,
+    /// Most improving moves applied to one child
+    #[serde(default = "default_education_max_moves")]
+    pub education_max_moves: usize,
+    /// Milliseconds the local search may spend on one child, 0 for no limit. A limit makes
+    /// runs depend on the speed of the machine, even with a seed
+    #[serde(default)]
+    pub education_time_limit: u64,
+    /// Only try local search moves between a patient and this many of its most correlated
+    /// patients, at most `NEIGHBOUR_LIST_SIZE`. 0 tries every move
+    #[serde(default = "default_granular_neighbours")]
+    pub granular_neighbours: usize,
+    /// Weight of waiting and lateness against travel time when picking the granular neighbours,
+    /// 0 for the nearest patients
+    #[serde(default)]
+    pub granular_time_window_weight: f32,
     pub s: f32,
 
     pub tournament_size: i32,
 }
 
+fn default_output_dir() -> String {
+    "individuals".to_string()
+}
+
+fn default_time_warp_penalty() -> f32 {
+    1.
+}
+
+fn default_load_penalty() -> f32 {
+    10.
+}
+
+fn default_target_feasible_ratio() -> f32 {
+    0.2
+}
+
+fn default_penalty_adjust_frequency() -> i32 {
+    100
+}
+
+fn default_subpopulation_size() -> i32 {
+    75
+}
+
+fn default_diversity_neighbours() -> i32 {
+    5
+}
+
+fn default_biased_fitness_elite() -> i32 {
+    4
+}
+
+fn default_education_neighbourhoods() -> Vec<Neighbourhood> {
+    vec![
+        Neighbourhood::Relocate,
+        Neighbourhood::Swap,
+        Neighbourhood::TwoOpt,
+        Neighbourhood::OrOpt,
+        Neighbourhood::TwoOptStar,
+        Neighbourhood::CrossExchange,
+    ]
+}
+
+fn default_education_max_moves() -> usize {
+    1000
+}
+
+fn default_cross_exchange_max_length() -> usize {
+    3
+}
+
+fn default_ruin_removals() -> Vec<Removal> {
+    vec![Removal::Random, Removal::Worst, Removal::Shaw, Removal::Route, Removal::String]
+}
+
+fn default_ruin_insertions() -> Vec<Insertion> {
+    vec![Insertion::Greedy, Insertion::Regret, Insertion::Blink]
+}
+
+fn default_ruin_min_removed() -> usize {
+    5
+}
+
+fn default_ruin_max_removed() -> usize {
+    20
+}
+
+fn default_worst_removal_randomness() -> f32 {
+    3.
+}
+
+fn default_shaw_randomness() -> f32 {
+    6.
+}
+
+fn default_shaw_distance_weight() -> f32 {
+    9.
+}
+
+fn default_shaw_time_weight() -> f32 {
+    3.
+}
+
+fn default_sisr_max_string_length() -> usize {
+    10
+}
+
+fn default_regret_k() -> usize {
+    3
+}
+
+fn default_blink_rate() -> f32 {
+    0.01
+}
+
+fn default_granular_neighbours() -> usize {
+    20
+}
+
 impl Config {
     pub fn new(path: &str) -> Self {
         let file_content = fs::read_to_string(path).expect("Failed to read file");
         serde_yaml::from_str(&file_content).unwrap()
     }
+
+    /// Mutations to apply, in order, with their rates
+    pub fn mutation_rates(&self) -> Vec<(String, f32)> {
+        if !self.mutations.is_empty() {
+            return self.mutations.iter().map(|m| (m.name.clone(), m.rate)).collect();
+        }
+        vec![
+            ("heuristic_cluster".to_string(), self.heuristic_cluster_mutation_rate),
+            ("heuristic_swap".to_string(), self.heuristic_swap_mutation_rate),
+            ("heuristic_random_swap".to_string(), self.heuristic_random_swap_mutation_rate),
+            ("large_neighbourhood".to_string(), self.large_neighbourhood_mutation_rate),
+            ("insert".to_string(), self.insert_mutation_rate),
+            ("two_opt_star".to_string(), self.two_opt_star_mutation_rate),
+            ("cross_exchange".to_string(), self.cross_exchange_mutation_rate),
+            ("two_opt".to_string(), self.two_opt_mutation_rate),
+            ("or_opt".to_string(), self.or_opt_mutation_rate),
+            ("ruin_recreate".to_string(), self.ruin_recreate_mutation_rate),
+        ]
+    }
+
+    /// Read a config file and apply `key=value` overrides on top of it.
+    /// Values are parsed as YAML, so `run_time=60`, `use_islands=false` and
+    /// `crossover_fn=Visma` all get the type the field expects.
+    pub fn from_file(path: &str, overrides: &[String]) -> Result<Self, Box<dyn Error>> {
+        let file_content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read config {}: {}", path, e))?;
+        let mut value: Value = serde_yaml::from_str(&file_content)?;
+        let mapping = value
+            .as_mapping_mut()
+            .ok_or_else(|| format!("Config {} is not a YAML mapping", path))?;
+
+        for override_str in overrides {
+            let (key, raw_value) = override_str
+                .split_once('=')
+                .ok_or_else(|| format!("Invalid override '{}', expected KEY=VALUE", override_str))?;
+            let key = Value::String(key.trim().to_string());
+            if !mapping.contains_key(&key) {
+                eprintln!("Warning: override key {:?} is not in {}", key.as_str().unwrap(), path);
+            }
+            let parsed: Value = serde_yaml::from_str(raw_value.trim())?;
+            mapping.insert(key, parsed);
+        }
+
+        let config: Config = serde_yaml::from_value(value)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Fails for settings the algorithm cannot run with
+    pub fn validate(&self) -> Result<(), Box<dyn Error>> {
+        // Each subpopulation can end up holding every individual, so an empty limit on either
+        // can empty the population
+        if self.subpopulations && (self.feasible_population_size < 1 || self.infeasible_population_size < 1) {
+            return Err(format!(
+                "feasible_population_size ({}) and infeasible_population_size ({}) must both be at least 1 with subpopulations",
+                self.feasible_population_size, self.infeasible_population_size
+            ).into());
+        }
+        if self.ruin_recreate_mutation_rate > 0. {
+            if self.ruin_removals.is_empty() || self.ruin_insertions.is_empty() {
+                return Err("ruin_removals and ruin_insertions need at least one heuristic each with ruin_recreate_mutation_rate above 0".into());
+            }
+            if self.ruin_max_removed < 1 || self.ruin_min_removed > self.ruin_max_removed {
+                return Err(format!(
+                    "ruin_min_removed ({}) can not be above ruin_max_removed ({}), which must be at least 1",
+                    self.ruin_min_removed, self.ruin_max_removed
+                ).into());
+            }
+        }
+        Ok(())
+    }
 }
\ No newline at end of file
@@ -1,4 +1,4 @@
-use serde::{Deserialize, Serialize, Deserializer};
+use serde::{Deserialize, Serialize, Deserializer, Serializer};
 
 use serde_json;
 use serde_json::from_reader;
@@ -7,16 +7,23 @@ use std::error::Error;
 use std::io::BufReader;
 use std::fs::File;
 use std::collections::HashMap;
+use std::path::Path;
 use crate::structs::config::Config;
+use crate::structs::solomon::read_from_solomon;
+use crate::structs::travel_times::{DistanceMetric, TravelTimes};
 
-#[derive(Deserialize, Serialize, Debug)]
+/// Length of the nearest neighbour lists in `Info::travel_times_sorted`
+pub const NEIGHBOUR_LIST_SIZE: usize = 100;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct Info {
     pub instance_name: String,
     pub nbr_nurses: u32,
     pub capacity_nurse: u32,
     pub benchmark: f32,
     pub depot: Depot,
-    pub travel_times: Vec<Vec<f32>>,
+    pub travel_times: TravelTimes,
+    /// For every node, the closest other nodes, nearest first. At most `NEIGHBOUR_LIST_SIZE` long
     pub travel_times_sorted: Vec<Vec<usize>>,
 
     #[serde(deserialize_with = "patients_to_vec")]
@@ -29,50 +36,84 @@ pub struct InfoRaw {
     pub capacity_nurse: u32,
     pub benchmark: f32,
     pub depot: Depot,
-    pub travel_times: Vec<Vec<f32>>,
+    /// Computed from the coordinates with `distance_metric` when left out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub travel_times: Option<TravelTimes>,
+    #[serde(default)]
+    pub distance_metric: DistanceMetric,
 
-    #[serde(deserialize_with = "patients_to_vec")]
+    #[serde(deserialize_with = "patients_to_vec", serialize_with = "patients_to_map")]
     pub patients: Vec<Patient>
 }
 
-impl From<InfoRaw> for Info {
-    fn from(raw: InfoRaw) -> Self {
-        let mut travel_times_sorted: Vec<Vec<usize>> = Vec::new();
-
-        for patient_num in 0..raw.travel_times.len() {
-            let cloone = raw.travel_times[patient_num].clone();
-            let mut travel_times_indexed: Vec<(usize, &f32)> = cloone.iter().enumerate().collect();
-            travel_times_indexed.sort_by(|a, b| a.1.partial_cmp(b.1).unwrap_or(std::cmp::Ordering::Equal));
-            let mut sorted_times: Vec<usize> = travel_times_indexed.into_iter().map(|(index, _)| index).collect();
-            sorted_times.remove(0);
-            travel_times_sorted.push(sorted_times);
-        }
+/// The `n` nodes closest to `node`, nearest first
+pub fn nearest_neighbours(travel_times: &TravelTimes, node: usize, n: usize) -> Vec<usize> {
+    let row = &travel_times[node];
+    let mut neighbours: Vec<usize> = (0..travel_times.len()).filter(|&other| other != node).collect();
+    let by_time = |a: &usize, b: &usize| row[*a].total_cmp(&row[*b]).then(a.cmp(b));
 
+    // Only sort the part of the list that is kept
+    if n < neighbours.len() {
+        neighbours.select_nth_unstable_by(n, by_time);
+        neighbours.truncate(n);
+    }
+    neighbours.sort_by(by_time);
+    neighbours
+}
+
+impl InfoRaw {
+    /// Depot followed by the patients, matching the travel time matrix indices
+    pub fn coordinates(&self) -> Vec<(f64, f64)> {
+        std::iter::once((self.depot.x_coord, self.depot.y_coord))
+            .chain(self.patients.iter().map(|patient| (patient.x_coord, patient.y_coord)))
+            .collect()
+    }
+}
+
+impl TryFrom<InfoRaw> for Info {
+    type Error = Box<dyn Error>;
+
+    /// Fails if the travel time matrix does not have a row and a column for the depot and every
+    /// patient
+    fn try_from(raw: InfoRaw) -> Result<Self, Self::Error> {
+        let travel_times = match raw.travel_times {
+            Some(travel_times) => travel_times,
+            None => TravelTimes::from_coordinates(&raw.coordinates(), raw.distance_metric),
+        };
+        if travel_times.len() != raw.patients.len() + 1 {
+            return Err(format!(
+                "Instance {} has {} patients but a {}x{} travel time matrix",
+                raw.instance_name, raw.patients.len(), travel_times.len(), travel_times.len()
+            ).into());
+        }
+        let travel_times_sorted: Vec<Vec<usize>> = (0..travel_times.len())
+            .map(|node| nearest_neighbours(&travel_times, node, NEIGHBOUR_LIST_SIZE))
+            .collect();
 
-        Info {
+        Ok(Info {
             instance_name: raw.instance_name,
             nbr_nurses: raw.nbr_nurses,
             capacity_nurse: raw.capacity_nurse,
             benchmark: raw.benchmark,
             depot: raw.depot,
-            travel_times: raw.travel_times,
+            travel_times,
             travel_times_sorted, // Populate the sorted travel times
             patients: raw.patients,
-        }
+        })
     }
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct Depot {
     pub return_time: u32,
-    pub x_coord: i32,
-    pub y_coord: i32
+    pub x_coord: f64,
+    pub y_coord: f64
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, Copy)]
 pub struct Patient {
-    pub x_coord: i32,
-    pub y_coord: i32,
+    pub x_coord: f64,
+    pub y_coord: f64,
     pub demand: u32,
     pub start_time: u32,
     pub end_time: u32,
@@ -113,10 +154,31 @@ where
     Ok(patients_vec.into_iter().map(|(_, patient)| patient).collect())
 }
 
+/// Write patients keyed by their one-based number, the layout `patients_to_vec` reads
+fn patients_to_map<S>(patients: &[Patient], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.collect_map(patients.iter().enumerate().map(|(index, patient)| ((index + 1).to_string(), patient)))
+}
+
+/// Read the instance given by the config. `.txt` files are read as Solomon instances,
+/// everything else as the course JSON format
+pub fn read_instance(config: &Config) -> Result<Info, Box<dyn Error>> {
+    match &config.instance_path {
+        Some(path) if path.to_lowercase().ends_with(".txt") => read_from_solomon(Path::new(path)),
+        _ => read_from_json(config),
+    }
+}
+
 pub fn read_from_json(config: &Config) -> Result<Info, Box<dyn Error>> {
-    let f = File::open("train/".to_string() + &*config.file_name + ".json")?;
+    let path = match &config.instance_path {
+        Some(path) => path.clone(),
+        None => "train/".to_string() + &*config.file_name + ".json",
+    };
+    let f = File::open(&path).map_err(|e| format!("Failed to open instance {}: {}", path, e))?;
     let reader = BufReader::new(f);
     let info_raw: InfoRaw = from_reader(reader)?;
-    let info = Info::from(info_raw);
+    let info = Info::try_from(info_raw).map_err(|e| format!("{}: {}", path, e))?;
     Ok(info)
 }
\ No newline at end of file
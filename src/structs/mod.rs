@@ -1,3 +1,6 @@
 pub mod nurse;
 pub mod io;
-pub(crate) mod config;
\ No newline at end of file
+pub mod config;
+pub mod solomon;
+pub mod stats;
+pub mod travel_times;
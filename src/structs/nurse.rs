@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use std::hash::{Hash, Hasher};
 
-#[derive(Deserialize, Serialize, Debug, Clone, Eq, Hash, PartialEq)]
+#[derive(Deserialize, Serialize, Debug, Clone, Default, Eq, Hash, PartialEq)]
 pub struct Nurse {
     pub capacity: i32,
     pub route: Vec<i32>
@@ -20,6 +20,14 @@ pub struct Individual {
     pub feasible: bool
 }
 
+impl Individual {
+    /// Feasible individuals beat infeasible ones, then lower fitness wins
+    pub fn is_better_than(&self, other: &Individual) -> bool {
+        (self.feasible && !other.feasible)
+            || (self.feasible == other.feasible && self.fitness < other.fitness)
+    }
+}
+
 impl Hash for Individual {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.nurses.hash(state);
@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use crate::structs::io::{Depot, Info, InfoRaw, Patient};
+use crate::structs::travel_times::{DistanceMetric, TravelTimes};
+
+/// Read a Solomon VRPTW instance (`C101.txt`) or one of the larger Gehring & Homberger
+/// instances (`R1_2_1.txt` up to `RC2_10_10.txt`), which use the same layout.
+pub fn read_from_solomon(path: &Path) -> Result<Info, Box<dyn Error>> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to open instance {}: {}", path.display(), e))?;
+    parse_solomon(&content)
+}
+
+/// Build an instance from the Solomon text format.
+///
+/// Customer 0 is the depot and its due date becomes `depot.return_time`. Vehicles become
+/// nurses. Solomon due dates are the latest start of service, while `end_time` is the
+/// latest end of care, so the service time is added to the due date.
+/// Travel times are the unrounded Euclidean distances. The best known solution is not
+/// part of the format, so `benchmark` is set to infinity.
+pub fn parse_solomon(content: &str) -> Result<Info, Box<dyn Error>> {
+    let mut lines = content.lines().map(|line| line.trim()).filter(|line| !line.is_empty());
+
+    let instance_name = lines.next().ok_or("Empty Solomon instance")?.to_string();
+
+    let mut vehicles: Option<(u32, u32)> = None;
+    let mut rows: Vec<Vec<&str>> = Vec::new();
+    while let Some(line) = lines.next() {
+        if line.eq_ignore_ascii_case("VEHICLE") {
+            // Skip the "NUMBER CAPACITY" header
+            lines.next();
+            let fields: Vec<&str> = lines.next().ok_or("Missing vehicle line")?.split_whitespace().collect();
+            if fields.len() != 2 {
+                return Err(format!("Expected vehicle number and capacity, got '{}'", fields.join(" ")).into());
+            }
+            vehicles = Some((parse_field(fields[0], "vehicle number")?, parse_field(fields[1], "capacity")?));
+        } else if line.eq_ignore_ascii_case("CUSTOMER") {
+            // Skip the column header
+            lines.next();
+        } else if line.starts_with(|c: char| c.is_ascii_digit()) {
+            rows.push(line.split_whitespace().collect());
+        }
+    }
+
+    let (nbr_nurses, capacity_nurse) = vehicles.ok_or("Missing VEHICLE section")?;
+    if rows.len() < 2 {
+        return Err("Solomon instance needs a depot and at least one customer".into());
+    }
+
+    let mut depot: Option<Depot> = None;
+    let mut patients: HashMap<usize, Patient> = HashMap::new();
+    let mut coordinates: Vec<(f64, f64)> = vec![(0., 0.); rows.len()];
+    for row in &rows {
+        if row.len() != 7 {
+            return Err(format!("Expected 7 columns in customer line, got '{}'", row.join(" ")).into());
+        }
+        let id: usize = parse_field(row[0], "customer number")?;
+        let x_coord: f64 = parse_field(row[1], "x coordinate")?;
+        let y_coord: f64 = parse_field(row[2], "y coordinate")?;
+        let demand: u32 = parse_field(row[3], "demand")?;
+        let ready_time: u32 = parse_field(row[4], "ready time")?;
+        let due_date: u32 = parse_field(row[5], "due date")?;
+        let service_time: u32 = parse_field(row[6], "service time")?;
+
+        if id >= rows.len() {
+            return Err(format!("Customer number {} is larger than the number of customers", id).into());
+        }
+        coordinates[id] = (x_coord, y_coord);
+
+        if id == 0 {
+            depot = Some(Depot { return_time: due_date, x_coord, y_coord });
+        } else {
+            let patient = Patient {
+                x_coord,
+                y_coord,
+                demand,
+                start_time: ready_time,
+                end_time: due_date + service_time,
+                care_time: service_time,
+            };
+            if patients.insert(id, patient).is_some() {
+                return Err(format!("Customer {} is listed twice", id).into());
+            }
+        }
+    }
+
+    let depot = depot.ok_or("Missing depot (customer 0)")?;
+    let mut patients_vec = Vec::with_capacity(patients.len());
+    for id in 1..rows.len() {
+        patients_vec.push(patients.remove(&id).ok_or_else(|| format!("Missing customer {}", id))?);
+    }
+
+    let travel_times = TravelTimes::from_coordinates(&coordinates, DistanceMetric::Euclidean);
+
+    Info::try_from(InfoRaw {
+        instance_name,
+        nbr_nurses,
+        capacity_nurse,
+        benchmark: f32::INFINITY,
+        depot,
+        travel_times: Some(travel_times),
+        distance_metric: DistanceMetric::Euclidean,
+        patients: patients_vec,
+    })
+}
+
+fn parse_field<T: std::str::FromStr>(field: &str, name: &str) -> Result<T, Box<dyn Error>> {
+    field.parse::<T>().map_err(|_| format!("Invalid {} '{}'", name, field).into())
+}
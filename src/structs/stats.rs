@@ -0,0 +1,24 @@
+use std::time::Duration;
+
+use serde::Serialize;
+
+/// Snapshot of a population, taken every `log_frequency` generations.
+#[derive(Serialize, Debug, Clone)]
+pub struct GenerationStats {
+    pub island: usize,
+    pub generation: i32,
+    pub best_fitness: f32,
+    pub avg_fitness: f32,
+    pub elapsed: Duration,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct RunStats {
+    /// Generations run, summed over all islands
+    pub generations: usize,
+    /// Number of times a stagnated population was scrambled
+    pub restarts: usize,
+    pub islands: usize,
+    pub elapsed: Duration,
+    pub history: Vec<GenerationStats>,
+}
@@ -0,0 +1,116 @@
+use std::ops::Index;
+
+use serde::{Deserialize, Serialize};
+
+/// How travel times are derived from coordinates when an instance has no matrix
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Default)]
+pub enum DistanceMetric {
+    #[default]
+    Euclidean,
+    /// Euclidean distance rounded to the nearest integer, as in TSPLIB
+    RoundedEuclidean,
+    Manhattan,
+    /// Great circle distance in kilometres, with `x_coord` as longitude and `y_coord` as latitude
+    Haversine,
+}
+
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+impl DistanceMetric {
+    pub fn distance(self, from: (f64, f64), to: (f64, f64)) -> f64 {
+        let (dx, dy) = (from.0 - to.0, from.1 - to.1);
+        match self {
+            DistanceMetric::Euclidean => (dx * dx + dy * dy).sqrt(),
+            DistanceMetric::RoundedEuclidean => (dx * dx + dy * dy).sqrt().round(),
+            DistanceMetric::Manhattan => dx.abs() + dy.abs(),
+            DistanceMetric::Haversine => {
+                let (lat1, lat2) = (from.1.to_radians(), to.1.to_radians());
+                let a = (dy.to_radians() / 2.).sin().powi(2)
+                    + lat1.cos() * lat2.cos() * (dx.to_radians() / 2.).sin().powi(2);
+                2. * EARTH_RADIUS_KM * a.sqrt().min(1.).asin()
+            }
+        }
+    }
+}
+
+/// Square travel time matrix stored as one flat vector. Node 0 is the depot and node
+/// `p + 1` is patient `p`. Indexing a row gives a slice, so `travel_times[from][to]` works
+/// like it does for nested vectors, without one allocation per row.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(try_from = "Vec<Vec<f32>>", into = "Vec<Vec<f32>>")]
+pub struct TravelTimes {
+    size: usize,
+    times: Vec<f32>,
+    /// Longest travel time, 0 without nodes
+    max: f32,
+}
+
+impl TravelTimes {
+    /// Distances between all pairs of coordinates under the given metric
+    pub fn from_coordinates(coordinates: &[(f64, f64)], metric: DistanceMetric) -> Self {
+        let size = coordinates.len();
+        let mut times = Vec::with_capacity(size * size);
+        for &from in coordinates {
+            for &to in coordinates {
+                times.push(metric.distance(from, to) as f32);
+            }
+        }
+        TravelTimes::new(size, times)
+    }
+
+    fn new(size: usize, times: Vec<f32>) -> Self {
+        let max = times.iter().copied().fold(0., f32::max);
+        TravelTimes { size, times, max }
+    }
+
+    /// Number of nodes, including the depot
+    pub fn len(&self) -> usize {
+        self.size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    pub fn get(&self, from: usize, to: usize) -> f32 {
+        self.times[from * self.size + to]
+    }
+
+    /// Longest travel time between any two nodes
+    pub fn max(&self) -> f32 {
+        self.max
+    }
+}
+
+impl Index<usize> for TravelTimes {
+    type Output = [f32];
+
+    fn index(&self, from: usize) -> &[f32] {
+        &self.times[from * self.size..(from + 1) * self.size]
+    }
+}
+
+impl TryFrom<Vec<Vec<f32>>> for TravelTimes {
+    type Error = String;
+
+    fn try_from(rows: Vec<Vec<f32>>) -> Result<Self, Self::Error> {
+        let size = rows.len();
+        let mut times = Vec::with_capacity(size * size);
+        for (row_idx, row) in rows.into_iter().enumerate() {
+            if row.len() != size {
+                return Err(format!("Travel time row {} has {} entries, expected {}", row_idx, row.len(), size));
+            }
+            times.extend(row);
+        }
+        Ok(TravelTimes::new(size, times))
+    }
+}
+
+impl From<TravelTimes> for Vec<Vec<f32>> {
+    fn from(travel_times: TravelTimes) -> Self {
+        if travel_times.size == 0 {
+            return Vec::new();
+        }
+        travel_times.times.chunks(travel_times.size).map(|row| row.to_vec()).collect()
+    }
+}
@@ -0,0 +1,57 @@
+#[cfg(test)]
+use clap::CommandFactory;
+#[cfg(test)]
+use crate::cli::Cli;
+#[cfg(test)]
+use crate::structs::config::{Config, CrossoverFN};
+
+#[test]
+fn test_cli_definition() {
+    Cli::command().debug_assert();
+}
+
+#[test]
+fn test_config_overrides() {
+    let overrides = vec![
+        "run_time=5".to_string(),
+        "use_islands=false".to_string(),
+        "crossover_fn=Visma".to_string(),
+        "file_name=train_3".to_string(),
+    ];
+    let config = Config::from_file("config/config_test.yaml", &overrides).unwrap();
+    assert_eq!(config.run_time, 5);
+    assert!(!config.use_islands);
+    assert!(matches!(config.crossover_fn, CrossoverFN::Visma));
+    assert_eq!(config.file_name, "train_3");
+
+    assert!(Config::from_file("config/config_test.yaml", &["run_time".to_string()]).is_err());
+    assert!(Config::from_file("config/config_test.yaml", &["run_time=soon".to_string()]).is_err());
+}
+
+#[test]
+fn test_config_rejects_empty_subpopulations() {
+    for sizes in [("0", "0"), ("1", "0"), ("0", "1")] {
+        let overrides = vec![
+            "subpopulations=true".to_string(),
+            format!("feasible_population_size={}", sizes.0),
+            format!("infeasible_population_size={}", sizes.1),
+        ];
+        assert!(Config::from_file("config/config_test.yaml", &overrides).is_err());
+    }
+    let overrides = vec!["subpopulations=true".to_string(), "feasible_population_size=1".to_string(), "infeasible_population_size=1".to_string()];
+    assert!(Config::from_file("config/config_test.yaml", &overrides).is_ok());
+}
+
+#[test]
+fn test_config_rejects_bad_ruin_settings() {
+    for settings in [&["ruin_removals=[]"], &["ruin_insertions=[]"], &["ruin_max_removed=0"], &["ruin_min_removed=21"]] {
+        let mut overrides = vec!["ruin_recreate_mutation_rate=0.1".to_string(), "ruin_max_removed=20".to_string()];
+        overrides.extend(settings.iter().map(|setting| setting.to_string()));
+        assert!(Config::from_file("config/config_test.yaml", &overrides).is_err(), "{:?}", settings);
+    }
+    let overrides = vec!["ruin_recreate_mutation_rate=0.1".to_string(), "ruin_min_removed=5".to_string(), "ruin_max_removed=5".to_string()];
+    assert!(Config::from_file("config/config_test.yaml", &overrides).is_ok());
+    // Not used without the mutation
+    let overrides = vec!["ruin_recreate_mutation_rate=0".to_string(), "ruin_removals=[]".to_string()];
+    assert!(Config::from_file("config/config_test.yaml", &overrides).is_ok());
+}
@@ -0,0 +1,134 @@
+#[cfg(test)]
+use crate::genetic::crossover::{edge_recombination_crossover, order_crossover_tour, route_based_crossover, srex_crossover};
+#[cfg(test)]
+use crate::genetic::operators::OperatorRegistry;
+#[cfg(test)]
+use crate::genetic::random::new_rng;
+#[cfg(test)]
+use crate::genetic::split::giant_tour;
+#[cfg(test)]
+use crate::structs::config::{Config, CrossoverFN};
+#[cfg(test)]
+use crate::structs::nurse::Individual;
+#[cfg(test)]
+use crate::test::fixtures::{assert_visits_every_patient_once, initial_individual, instance};
+
+#[test]
+fn test_crossovers_keep_every_patient() {
+    let info = instance(40, 0.5, 11);
+    let mut rng = new_rng(Some(11));
+
+    for crossover_fn in [CrossoverFN::Order, CrossoverFN::EdgeRecombination, CrossoverFN::RouteBased, CrossoverFN::Srex] {
+        let mut config = Config::new("config/config_test.yaml");
+        config.crossover_rate = 1.;
+        config.crossover_fn = crossover_fn;
+        let crossover = OperatorRegistry::default().resolve(&config).unwrap().crossover;
+
+        for _ in 0..20 {
+            let parent1 = initial_individual(&info, &config, &mut rng);
+            let parent2 = initial_individual(&info, &config, &mut rng);
+            let (child1, child2) = crossover.crossover(&parent1, &parent2, &mut rng, &info, &config);
+            assert_visits_every_patient_once(&child1.nurses, &info);
+            assert_visits_every_patient_once(&child2.nurses, &info);
+        }
+    }
+}
+
+#[test]
+fn test_order_crossover_keeps_the_slice_in_place() {
+    let info = instance(10, 0.5, 1);
+    let kept: Vec<i32> = (0..10).collect();
+    let other: Vec<i32> = (0..10).rev().collect();
+
+    // Positions 3 to 6 from the first tour, the rest in the order of the other one from position 7 on
+    assert_eq!(order_crossover_tour(&kept, &other, 3, 6, &info), vec![9, 8, 7, 3, 4, 5, 6, 2, 1, 0]);
+    assert_eq!(order_crossover_tour(&kept, &other, 0, 9, &info), kept);
+}
+
+#[test]
+fn test_edge_recombination_follows_the_parents_edges() {
+    let info = instance(40, 0.5, 12);
+    let mut config = Config::new("config/config_test.yaml");
+    config.crossover_rate = 1.;
+    let mut rng = new_rng(Some(12));
+
+    // With one parent twice the only edges form a cycle, walked one way or the other
+    let parent = initial_individual(&info, &config, &mut rng);
+    let tour = giant_tour(&parent);
+    let reversed: Vec<i32> = tour[..1].iter().chain(tour[1..].iter().rev()).copied().collect();
+    for _ in 0..5 {
+        let (child, _) = edge_recombination_crossover(&parent, &parent, &mut rng, &info, &config);
+        let child_tour = giant_tour(&child);
+        assert!(child_tour == tour || child_tour == reversed);
+    }
+
+    // Apart from restarts at dead ends, every step of a child is an edge of a parent tour
+    let parent2 = initial_individual(&info, &config, &mut rng);
+    let tours = [tour, giant_tour(&parent2)];
+    let is_parent_edge = |a: i32, b: i32| tours.iter().any(|tour| {
+        let i = tour.iter().position(|p| *p == a).unwrap();
+        tour[(i + 1) % tour.len()] == b || tour[(i + tour.len() - 1) % tour.len()] == b
+    });
+    let (child1, child2) = edge_recombination_crossover(&parent, &parent2, &mut rng, &info, &config);
+    for child in [child1, child2] {
+        let child_tour = giant_tour(&child);
+        let parent_edges = child_tour.windows(2).filter(|pair| is_parent_edge(pair[0], pair[1])).count();
+        assert!(parent_edges >= child_tour.len() * 9 / 10, "{} of {} edges", parent_edges, child_tour.len() - 1);
+    }
+}
+
+#[test]
+fn test_route_based_crossover_of_one_parent_keeps_its_routes() {
+    let info = instance(40, 0.5, 13);
+    let mut config = Config::new("config/config_test.yaml");
+    config.crossover_rate = 1.;
+    let mut rng = new_rng(Some(13));
+    let sorted_routes = |individual: &Individual| {
+        let mut routes: Vec<Vec<i32>> = individual.nurses.iter().map(|nurse| nurse.route.clone()).filter(|route| !route.is_empty()).collect();
+        routes.sort();
+        routes
+    };
+
+    // Routes not taken from the first copy are taken whole from the second
+    let parent = initial_individual(&info, &config, &mut rng);
+    for _ in 0..5 {
+        let (child1, child2) = route_based_crossover(&parent, &parent, &mut rng, &info, &config);
+        assert_eq!(sorted_routes(&child1), sorted_routes(&parent));
+        assert_eq!(sorted_routes(&child2), sorted_routes(&parent));
+    }
+}
+
+#[test]
+fn test_srex_takes_whole_routes_from_the_other_parent() {
+    let info = instance(40, 0.5, 14);
+    let mut config = Config::new("config/config_test.yaml");
+    config.crossover_rate = 1.;
+    let mut rng = new_rng(Some(14));
+    // Left over patients may be inserted into a taken route, so it only has to appear in order
+    let contains_route = |child: &Individual, route: &[i32]| child.nurses.iter().any(|nurse| {
+        let mut patients = nurse.route.iter();
+        route.iter().all(|p| patients.any(|q| q == p))
+    });
+    let non_empty_routes = |individual: &Individual| -> Vec<Vec<i32>> {
+        individual.nurses.iter().map(|nurse| nurse.route.clone()).filter(|route| !route.is_empty()).collect()
+    };
+
+    let mut compared = 0;
+    for _ in 0..10 {
+        let parent1 = initial_individual(&info, &config, &mut rng);
+        let parent2 = initial_individual(&info, &config, &mut rng);
+        let (routes1, routes2) = (non_empty_routes(&parent1), non_empty_routes(&parent2));
+        if routes1.iter().any(|route| routes2.contains(route)) {
+            continue;
+        }
+
+        let (child1, child2) = srex_crossover(&parent1, &parent2, &mut rng, &info, &config);
+        for (child, base, donor) in [(&child1, &routes1, &routes2), (&child2, &routes2, &routes1)] {
+            assert!(donor.iter().any(|route| contains_route(child, route)));
+            // At least one route of the base parent was given up
+            assert!(base.iter().any(|route| !contains_route(child, route)));
+        }
+        compared += 1;
+    }
+    assert!(compared > 0);
+}
@@ -0,0 +1,73 @@
+#[cfg(test)]
+use crate::genetic::diversity::{biased_fitness, broken_pairs_distance, Neighbours};
+#[cfg(test)]
+use crate::structs::config::Config;
+#[cfg(test)]
+use crate::structs::nurse::{Individual, Nurse};
+
+#[cfg(test)]
+fn individual(routes: &[&[i32]], fitness: f32) -> Individual {
+    Individual {
+        nurses: routes.iter().map(|route| Nurse { capacity: 0, route: route.to_vec() }).collect(),
+        fitness,
+        feasible: true,
+    }
+}
+
+#[test]
+fn test_broken_pairs_distance() {
+    let a = individual(&[&[0, 1, 2], &[3]], 0.);
+    assert_eq!(broken_pairs_distance(&a, &a), 0.);
+    // Driving a route the other way round keeps every pair
+    assert_eq!(broken_pairs_distance(&a, &individual(&[&[3], &[2, 1, 0]], 0.)), 0.);
+
+    let b = individual(&[&[3, 1, 0, 2]], 0.);
+    let distance = broken_pairs_distance(&b, &a);
+    assert!(distance > 0. && distance <= 1.);
+    let singletons = individual(&[&[0], &[1], &[2], &[3]], 0.);
+    // Patients 1 and 2 lose their neighbours and stop starting a route, which counts once each
+    assert_eq!(broken_pairs_distance(&singletons, &individual(&[&[0, 1, 2, 3]], 0.)), 0.5);
+    // The other way round only the first patient keeps its place
+    assert_eq!(broken_pairs_distance(&individual(&[&[0, 1, 2, 3]], 0.), &singletons), 0.75);
+}
+
+#[test]
+fn test_neighbours_distance() {
+    let a = Neighbours::new(&individual(&[&[0, 1, 2], &[3, 4]], 0.));
+    // Same routes with the nurses swapped and one route reversed
+    let b = Neighbours::new(&individual(&[&[4, 3], &[0, 1, 2]], 0.));
+    let c = Neighbours::new(&individual(&[&[4, 2, 0], &[1, 3]], 0.));
+
+    assert_eq!(a.distance(&b), 0.);
+    for (x, y) in [(&a, &c), (&c, &a), (&b, &c)] {
+        let distance = x.distance(y);
+        assert!(distance > 0. && distance <= 1.);
+    }
+    assert_eq!(a.distance(&c), b.distance(&c));
+}
+
+#[test]
+fn test_neighbours_distance_is_at_most_one() {
+    // Patient 4 has another successor in `b` and starts a route in `a` only
+    let a = Neighbours::new(&individual(&[&[0, 1], &[2, 3], &[4, 5]], 0.));
+    let b = Neighbours::new(&individual(&[&[0, 3, 4, 1, 5, 2]], 0.));
+
+    assert_eq!(a.distance(&b), 1.);
+    assert!(b.distance(&a) <= 1.);
+}
+
+#[test]
+fn test_biased_fitness_rewards_diversity() {
+    let mut config = Config::new("config/config_test.yaml");
+    config.diversity_neighbours = 1;
+    config.biased_fitness_elite = 0;
+
+    let best = individual(&[&[0, 1, 2, 3]], 10.);
+    let clone = individual(&[&[0, 1, 2, 3]], 11.);
+    let different = individual(&[&[3, 1], &[0, 2]], 12.);
+    let biased = biased_fitness(&[best, clone, different], &config);
+
+    // The worse but different individual beats the clone of the best one
+    assert!(biased[0] < biased[2]);
+    assert!(biased[2] < biased[1]);
+}
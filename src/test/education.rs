@@ -0,0 +1,64 @@
+#[cfg(test)]
+use crate::genetic::education::educate;
+#[cfg(test)]
+use crate::genetic::evaluate::fitness_population;
+#[cfg(test)]
+use crate::genetic::granular::Granular;
+#[cfg(test)]
+use crate::genetic::random::new_rng;
+#[cfg(test)]
+use crate::structs::config::{Config, Improvement, PenaltyModel};
+#[cfg(test)]
+use crate::structs::io::Info;
+#[cfg(test)]
+use crate::structs::nurse::Individual;
+#[cfg(test)]
+use crate::test::fixtures::{assert_visits_every_patient_once, initial_individual, instance};
+
+/// Education improves the individual, keeps every patient and leaves nothing to improve
+#[cfg(test)]
+fn assert_reaches_local_optimum(mut individual: Individual, info: &Info, config: &Config, granular: Option<&Granular>) {
+    let before = individual.fitness;
+    assert!(educate(&mut individual.nurses, info, config, granular) > 0);
+    fitness_population(std::slice::from_mut(&mut individual), info, config);
+    assert!(individual.fitness < before);
+    assert_visits_every_patient_once(&individual.nurses, info);
+    assert_eq!(educate(&mut individual.nurses, info, config, granular), 0);
+}
+
+#[test]
+fn test_education_reaches_a_local_optimum() {
+    let info = instance(25, 0.3, 4);
+    let mut rng = new_rng(Some(4));
+
+    for (penalty_model, improvement) in [(PenaltyModel::Legacy, Improvement::First), (PenaltyModel::TimeWarp, Improvement::Best)] {
+        let mut config = Config::new("config/config_test.yaml");
+        config.penalty_model = penalty_model;
+        config.education_improvement = improvement;
+        config.cross_exchange_reverse = improvement == Improvement::Best;
+
+        for _ in 0..3 {
+            assert_reaches_local_optimum(initial_individual(&info, &config, &mut rng), &info, &config, None);
+        }
+    }
+}
+
+#[test]
+fn test_granular_education() {
+    let info = instance(60, 0.3, 8);
+    let mut config = Config::new("config/config_test.yaml");
+    config.penalty_model = PenaltyModel::TimeWarp;
+    config.granular_neighbours = 8;
+
+    let granular = Granular::new(&info, &config);
+    assert!((0..60).all(|p| granular.neighbours(p).len() == 8 && !granular.neighbours(p).contains(&p)));
+    // Nearest first without a time window weight
+    assert_eq!(granular.neighbours(0)[0] as usize + 1, info.travel_times_sorted[1].iter().find(|node| **node != 0).copied().unwrap());
+    config.granular_time_window_weight = 1.;
+    let time_window_aware = Granular::new(&info, &config);
+
+    let mut rng = new_rng(Some(8));
+    for granular in [&granular, &time_window_aware] {
+        assert_reaches_local_optimum(initial_individual(&info, &config, &mut rng), &info, &config, Some(granular));
+    }
+}
@@ -0,0 +1,39 @@
+#[cfg(test)]
+use crate::genetic::evaluate::fitness_population;
+#[cfg(test)]
+use crate::genetic::initialize_population::feasible_init_individual;
+#[cfg(test)]
+use crate::genetic::random::GaRng;
+#[cfg(test)]
+use crate::structs::config::Config;
+#[cfg(test)]
+use crate::structs::io::Info;
+#[cfg(test)]
+use crate::structs::nurse::{Individual, Nurse};
+#[cfg(test)]
+use crate::util::generate::{generate_instance, GeneratorOptions};
+
+/// Generated instance, with the default generator options apart from these
+#[cfg(test)]
+pub(super) fn instance(patients: usize, time_window_tightness: f64, seed: u64) -> Info {
+    Info::try_from(generate_instance(&GeneratorOptions {
+        patients, time_window_tightness, seed: Some(seed), ..GeneratorOptions::default()
+    }).unwrap()).unwrap()
+}
+
+/// Individual from `feasible_init_individual`, evaluated
+#[cfg(test)]
+pub(super) fn initial_individual(info: &Info, config: &Config, rng: &mut GaRng) -> Individual {
+    let mut individuals = vec![feasible_init_individual(info, config, rng)];
+    fitness_population(&mut individuals, info, config);
+    individuals.pop().unwrap()
+}
+
+/// Every nurse of the instance is there and every patient is visited by exactly one of them
+#[cfg(test)]
+pub(super) fn assert_visits_every_patient_once(nurses: &[Nurse], info: &Info) {
+    assert_eq!(nurses.len(), info.nbr_nurses as usize);
+    let mut patients: Vec<i32> = nurses.iter().flat_map(|nurse| nurse.route.iter().copied()).collect();
+    patients.sort();
+    assert_eq!(patients, (0..info.patients.len() as i32).collect::<Vec<i32>>());
+}
@@ -0,0 +1,44 @@
+#[cfg(test)]
+use crate::structs::io::{Info, InfoRaw};
+#[cfg(test)]
+use crate::util::generate::{generate_instance, GeneratorOptions, Layout};
+
+#[test]
+fn test_generate_instance() {
+    let options = GeneratorOptions {
+        patients: 60,
+        layout: Layout::Mixed,
+        time_window_tightness: 0.8,
+        care_time: (5, 20),
+        seed: Some(3),
+        ..GeneratorOptions::default()
+    };
+    let raw = generate_instance(&options).unwrap();
+    let json = serde_json::to_string(&raw).unwrap();
+    assert_eq!(json, serde_json::to_string(&generate_instance(&options).unwrap()).unwrap());
+
+    let info = Info::try_from(serde_json::from_str::<InfoRaw>(&json).unwrap()).unwrap();
+    assert_eq!(info.patients.len(), 60);
+    assert_eq!(info.travel_times.len(), 61);
+    assert!(info.benchmark > 0.);
+    assert!(info.nbr_nurses * info.capacity_nurse >= info.patients.iter().map(|p| p.demand).sum::<u32>());
+
+    // Every patient can be visited by a nurse driving straight there and back
+    for (idx, patient) in info.patients.iter().enumerate() {
+        let to_patient = info.travel_times[0][idx + 1];
+        let care_done = (patient.start_time as f32).max(to_patient) + patient.care_time as f32;
+        assert!(care_done <= patient.end_time as f32, "patient {}", idx + 1);
+        assert!(care_done + info.travel_times[idx + 1][0] <= info.depot.return_time as f32);
+        assert!((5..=20).contains(&patient.care_time));
+    }
+}
+
+#[test]
+fn test_generate_rejects_bad_options() {
+    assert!(generate_instance(&GeneratorOptions { patients: 0, ..GeneratorOptions::default() }).is_err());
+    assert!(generate_instance(&GeneratorOptions { time_window_tightness: 1.5, ..GeneratorOptions::default() }).is_err());
+    assert!(generate_instance(&GeneratorOptions { demand: (1, 500), ..GeneratorOptions::default() }).is_err());
+    for grid_size in [0., -10., f64::NAN, f64::INFINITY] {
+        assert!(generate_instance(&GeneratorOptions { grid_size, ..GeneratorOptions::default() }).is_err());
+    }
+}
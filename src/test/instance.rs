@@ -0,0 +1,55 @@
+#[cfg(test)]
+use crate::structs::io::{Info, InfoRaw};
+#[cfg(test)]
+use crate::structs::travel_times::{DistanceMetric, TravelTimes};
+
+#[cfg(test)]
+fn raw_instance(metric: &str) -> InfoRaw {
+    let json = format!(r#"{{
+        "instance_name": "coords",
+        "nbr_nurses": 2,
+        "capacity_nurse": 100,
+        "benchmark": 10.0,
+        "distance_metric": "{}",
+        "depot": {{"return_time": 100, "x_coord": 0, "y_coord": 0}},
+        "patients": {{
+            "1": {{"x_coord": 3, "y_coord": 4, "demand": 1, "start_time": 0, "end_time": 50, "care_time": 1}},
+            "2": {{"x_coord": 1.5, "y_coord": 0.5, "demand": 1, "start_time": 0, "end_time": 50, "care_time": 1}}
+        }}
+    }}"#, metric);
+    serde_json::from_str(&json).unwrap()
+}
+
+#[cfg(test)]
+fn coordinates_only(metric: &str) -> Info {
+    Info::try_from(raw_instance(metric)).unwrap()
+}
+
+#[test]
+fn test_travel_time_matrix_must_match_patients() {
+    let mut raw = raw_instance("Euclidean");
+    raw.travel_times = Some(TravelTimes::try_from(vec![vec![0., 1.], vec![1., 0.]]).unwrap());
+    let error = Info::try_from(raw).unwrap_err();
+    assert!(error.to_string().contains("2x2"), "{}", error);
+}
+
+#[test]
+fn test_travel_times_from_coordinates() {
+    let info = coordinates_only("Euclidean");
+    assert_eq!(info.travel_times.len(), 3);
+    assert_eq!(info.travel_times[0][1], 5.);
+    assert_eq!(info.travel_times[1][0], 5.);
+    assert_eq!(info.travel_times_sorted[0], vec![2, 1]);
+    assert_eq!(info.travel_times.max(), 5.);
+
+    assert_eq!(coordinates_only("RoundedEuclidean").travel_times[0][2], 2.);
+    assert_eq!(coordinates_only("Manhattan").travel_times[0][1], 7.);
+}
+
+#[test]
+fn test_haversine() {
+    // Oslo to Trondheim is roughly 390 km in a straight line
+    let distance = DistanceMetric::Haversine.distance((10.75, 59.91), (10.40, 63.43));
+    assert!((distance - 392.).abs() < 5., "{}", distance);
+    assert_eq!(DistanceMetric::Haversine.distance((10.75, 59.91), (10.75, 59.91)), 0.);
+}
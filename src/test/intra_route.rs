@@ -0,0 +1,73 @@
+#[cfg(test)]
+use crate::genetic::evaluate::{fitness_nurse, Segment};
+#[cfg(test)]
+use crate::genetic::intra_route::{or_opt, polish, two_opt};
+#[cfg(test)]
+use crate::genetic::random::new_rng;
+#[cfg(test)]
+use crate::structs::config::{Config, PenaltyModel};
+#[cfg(test)]
+use crate::structs::io::Info;
+#[cfg(test)]
+use crate::structs::nurse::Nurse;
+#[cfg(test)]
+use crate::test::fixtures::{initial_individual, instance};
+
+#[cfg(test)]
+fn cost(route: &[i32], info: &Info, config: &Config) -> f32 {
+    fitness_nurse(&Nurse { capacity: 0, route: route.to_vec() }, info, config).0
+}
+
+#[test]
+fn test_two_opt_and_or_opt_keep_feasible_routes_feasible() {
+    let info = instance(30, 0.5, 12);
+    let mut config = Config::new("config/config_test.yaml");
+    config.penalty_model = PenaltyModel::TimeWarp;
+    let mut rng = new_rng(Some(12));
+
+    for _ in 0..5 {
+        let individual = initial_individual(&info, &config, &mut rng);
+        for nurse in &individual.nurses {
+            let feasible = Segment::of_route(&nurse.route, &info).is_feasible(&info);
+            for operator in [two_opt, or_opt] {
+                let mut route = nurse.route.clone();
+                let before = cost(&route, &info, &config);
+                if operator(&mut route, &info, &config) {
+                    assert!(cost(&route, &info, &config) < before);
+                }
+                if feasible {
+                    assert!(Segment::of_route(&route, &info).is_feasible(&info));
+                }
+                let mut sorted = route.clone();
+                sorted.sort();
+                let mut expected = nurse.route.clone();
+                expected.sort();
+                assert_eq!(sorted, expected);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_polish_leaves_local_optima() {
+    let info = instance(30, 0., 2);
+    let config = Config::new("config/config_test.yaml");
+    let mut rng = new_rng(Some(2));
+
+    let mut individual = initial_individual(&info, &config, &mut rng);
+    for nurse in individual.nurses.iter_mut() {
+        nurse.route.reverse();
+        let middle = nurse.route.len() / 2;
+        nurse.route.rotate_left(middle);
+    }
+    let scrambled: f32 = individual.nurses.iter().map(|nurse| cost(&nurse.route, &info, &config)).sum();
+
+    polish(&mut individual, &info, &config);
+    let polished: f32 = individual.nurses.iter().map(|nurse| cost(&nurse.route, &info, &config)).sum();
+    assert!((individual.fitness - polished).abs() < 1e-3);
+    assert!(polished < scrambled);
+    for nurse in individual.nurses.iter_mut() {
+        assert!(!two_opt(&mut nurse.route, &info, &config));
+        assert!(!or_opt(&mut nurse.route, &info, &config));
+    }
+}
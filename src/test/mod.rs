@@ -1 +1,20 @@
-mod mutation;
\ No newline at end of file
+mod fixtures;
+mod mutation;
+mod cli;
+mod operators;
+mod seed;
+mod solomon;
+mod instance;
+mod generate;
+mod validate;
+mod solution_file;
+mod route_cache;
+mod segment;
+mod penalty;
+mod population;
+mod diversity;
+mod split;
+mod crossover;
+mod education;
+mod intra_route;
+mod ruin_recreate;
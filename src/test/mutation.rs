@@ -3,6 +3,8 @@ use crate::genetic::initialize_population::init_population;
 #[cfg(test)]
 use crate::genetic::mutation::*;
 #[cfg(test)]
+use crate::genetic::random::new_rng;
+#[cfg(test)]
 use crate::structs::config::Config;
 #[cfg(test)]
 use crate::structs::io;
@@ -15,7 +17,7 @@ use crate::structs::nurse::Individual;
 pub fn get_initial() -> (Info, Config, Vec<Individual>) {
     let config = Config::new("config/config_test.yaml");
     let info = io::read_from_json(&config).unwrap();
-    let population = init_population(&info, &config);
+    let population = init_population(&info, &config, &mut new_rng(Some(1)));
     (info, config, population)
 }
 
@@ -35,7 +37,7 @@ fn legal(individual: &Individual) {
 fn test_heuristic_cluster_mutation() {
     let (info, config, population) = get_initial();
     let mut individual = population[0].clone();
-    let mut rng = rand::rng();
+    let mut rng = new_rng(Some(1));
 
     let individual_before = individual.clone();
 
@@ -49,4 +51,40 @@ fn test_heuristic_cluster_mutation() {
     }
     assert_ne!(individual_before, individual);
     legal(&individual);
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+fn patient_count(individual: &Individual) -> usize {
+    individual.nurses.iter().map(|nurse| nurse.route.len()).sum()
+}
+
+#[test]
+fn test_two_opt_star_mutation() {
+    let (info, config, population) = get_initial();
+    let mut individual = population[0].clone();
+    let mut rng = new_rng(Some(3));
+    let individual_before = individual.clone();
+
+    for _ in 0..20 {
+        two_opt_star_mutation(&mut individual.nurses, &mut rng, &info, &config);
+    }
+    assert_ne!(individual_before, individual);
+    assert_eq!(patient_count(&individual_before), patient_count(&individual));
+    legal(&individual);
+}
+
+#[test]
+fn test_cross_exchange_mutation() {
+    let (info, mut config, population) = get_initial();
+    config.cross_exchange_reverse = true;
+    let mut individual = population[0].clone();
+    let mut rng = new_rng(Some(3));
+    let individual_before = individual.clone();
+
+    for _ in 0..20 {
+        cross_exchange_mutation(&mut individual.nurses, &mut rng, &info, &config);
+    }
+    assert_ne!(individual_before, individual);
+    assert_eq!(patient_count(&individual_before), patient_count(&individual));
+    legal(&individual);
+}
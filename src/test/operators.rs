@@ -0,0 +1,58 @@
+#[cfg(test)]
+use crate::genetic::operators::{Mutation, OperatorRegistry};
+#[cfg(test)]
+use crate::genetic::random::GaRng;
+#[cfg(test)]
+use crate::structs::config::{Config, CrossoverFN};
+#[cfg(test)]
+use crate::structs::io::Info;
+#[cfg(test)]
+use crate::structs::nurse::{Individual, Nurse};
+
+#[cfg(test)]
+struct ReverseRoutes;
+
+#[cfg(test)]
+impl Mutation for ReverseRoutes {
+    fn mutate(&self, nurses: &mut [Nurse], _rng: &mut GaRng, _info: &Info, _config: &Config) {
+        nurses.iter_mut().for_each(|nurse| nurse.route.reverse());
+    }
+}
+
+#[test]
+fn test_custom_operators_by_name() {
+    let overrides = vec![
+        "mutations=[{name: reverse, rate: 1.0}, {name: heuristic_swap, rate: 0.5}]".to_string(),
+        "crossover_fn=my_crossover".to_string(),
+    ];
+    let config = Config::from_file("config/config_test.yaml", &overrides).unwrap();
+    assert_eq!(config.crossover_fn, CrossoverFN::Custom("my_crossover".to_string()));
+
+    let mut registry = OperatorRegistry::default();
+    assert!(registry.resolve(&config).is_err());
+
+    registry.register_mutation("reverse", ReverseRoutes);
+    registry.register_crossover("my_crossover", |p1: &Individual, p2: &Individual, _: &mut GaRng, _: &Info, _: &Config| {
+        (p1.clone(), p2.clone())
+    });
+    let operators = registry.resolve(&config).unwrap();
+    assert_eq!(operators.mutations.len(), 2);
+    assert_eq!(operators.mutations[0].1, 1.0);
+}
+
+#[test]
+fn test_legacy_mutation_rates() {
+    let config = Config::new("config/config_test.yaml");
+    let operators = OperatorRegistry::default().resolve(&config).unwrap();
+    assert_eq!(operators.mutations[0].1, config.heuristic_cluster_mutation_rate);
+    // Mutations with rate 0 are left out
+    let active = config.mutation_rates().iter().filter(|(_, rate)| *rate > 0.).count();
+    assert_eq!(operators.mutations.len(), active);
+    assert!(operators.mutations.iter().all(|(_, rate)| *rate > 0.));
+
+    let mut with_ruin = config.clone();
+    with_ruin.ruin_recreate_mutation_rate = 0.5;
+    let operators = OperatorRegistry::default().resolve(&with_ruin).unwrap();
+    assert_eq!(operators.mutations.len(), active + 1);
+    assert_eq!(operators.mutations[active].1, 0.5);
+}
@@ -0,0 +1,85 @@
+#[cfg(test)]
+use crate::genetic::genetic_algo::Evolution;
+#[cfg(test)]
+use crate::genetic::operators::OperatorRegistry;
+#[cfg(test)]
+use crate::genetic::penalty::PenaltyManager;
+#[cfg(test)]
+use crate::genetic::random::new_rng;
+#[cfg(test)]
+use crate::structs::config::{Config, PenaltyModel};
+#[cfg(test)]
+use crate::structs::io::Info;
+#[cfg(test)]
+use crate::structs::nurse::{Individual, Nurse};
+#[cfg(test)]
+use crate::test::fixtures::instance;
+#[cfg(test)]
+use crate::util::generate::{generate_instance, GeneratorOptions};
+
+#[test]
+fn test_penalties_follow_feasible_ratio() {
+    let info = Info::try_from(generate_instance(&GeneratorOptions {
+        patients: 20, time_window_tightness: 0.9, capacity: 50, seed: Some(2), ..GeneratorOptions::default()
+    }).unwrap()).unwrap();
+    let mut config = Config::new("config/config_test.yaml");
+    config.penalty_model = PenaltyModel::TimeWarp;
+    config.target_feasible_ratio = 0.5;
+    let (time_warp_penalty, load_penalty) = (config.time_warp_penalty, config.load_penalty);
+
+    // One nurse visiting everyone is both late and over capacity
+    let infeasible = Individual { nurses: vec![Nurse { capacity: 0, route: (0..20).collect() }], fitness: 0., feasible: false };
+    let feasible = Individual { nurses: vec![Nurse::new()], fitness: 0., feasible: true };
+
+    let mut penalties = PenaltyManager::default();
+    penalties.record(&[infeasible.clone(), infeasible.clone(), feasible.clone()], &info);
+    assert!(penalties.adjust(&mut config));
+    assert!(config.time_warp_penalty > time_warp_penalty);
+    assert!(config.load_penalty > load_penalty);
+
+    // Nothing recorded since the last adjustment
+    assert!(!penalties.adjust(&mut config));
+
+    let raised = config.time_warp_penalty;
+    penalties.record(&[feasible.clone(), feasible.clone(), feasible], &info);
+    assert!(penalties.adjust(&mut config));
+    assert!(config.time_warp_penalty < raised);
+
+    // Legacy only adapts the lateness factor
+    config.penalty_model = PenaltyModel::Legacy;
+    let factor = config.fitness_punishment_factor;
+    penalties.record(&[infeasible], &info);
+    assert!(penalties.adjust(&mut config));
+    assert!(config.fitness_punishment_factor > factor);
+}
+
+#[test]
+fn test_run_moves_penalties_towards_target_ratio() {
+    let info = instance(25, 0.5, 14);
+    let mut config = Config::new("config/config_test.yaml");
+    config.population_size = 20;
+    config.penalty_model = PenaltyModel::TimeWarp;
+    config.adaptive_penalties = true;
+    config.penalty_adjust_frequency = 1;
+    let weights = |config: &Config| (config.time_warp_penalty, config.load_penalty);
+    let initial = weights(&config);
+
+    // No ratio is above 1 or below 0, so the weights can only move one way
+    for (target, raise) in [(1., true), (0., false)] {
+        config.target_feasible_ratio = target;
+        let operators = OperatorRegistry::default().resolve(&config).unwrap();
+        let mut evolution = Evolution::new(&info, &config, &operators, new_rng(Some(14)));
+        for _ in 0..5 {
+            evolution.sort();
+            evolution.generation(None);
+        }
+
+        let (time_warp, load) = weights(&evolution.config);
+        if raise {
+            assert!(time_warp >= initial.0 && load >= initial.1);
+        } else {
+            assert!(time_warp <= initial.0 && load <= initial.1);
+        }
+        assert_ne!((time_warp, load), initial);
+    }
+}
@@ -0,0 +1,78 @@
+#[cfg(test)]
+use crate::genetic::genetic_algo::Evolution;
+#[cfg(test)]
+use crate::genetic::operators::OperatorRegistry;
+#[cfg(test)]
+use crate::genetic::parent_selection::{linear_ranking, parent_selection};
+#[cfg(test)]
+use crate::genetic::population::Subpopulations;
+#[cfg(test)]
+use crate::genetic::random::new_rng;
+#[cfg(test)]
+use crate::structs::config::Config;
+#[cfg(test)]
+use crate::structs::nurse::{Individual, Nurse};
+#[cfg(test)]
+use crate::test::fixtures::instance;
+
+#[cfg(test)]
+fn individual(patient: i32, fitness: f32, feasible: bool) -> Individual {
+    Individual { nurses: vec![Nurse { capacity: 0, route: vec![patient] }], fitness, feasible }
+}
+
+#[test]
+fn test_subpopulation_survivors() {
+    let mut config = Config::new("config/config_test.yaml");
+    config.feasible_population_size = 2;
+    config.infeasible_population_size = 3;
+
+    let mut subpopulations = Subpopulations::new(vec![
+        individual(1, 50., true),
+        individual(2, 40., true),
+        individual(2, 40., true),
+        individual(3, 60., true),
+        individual(4, 10., false),
+        individual(5, 30., false),
+    ]);
+    subpopulations.select_survivors(&config);
+
+    // The clone of 2 does not take the place of 1
+    let fitnesses: Vec<f32> = subpopulations.feasible.iter().map(|i| i.fitness).collect();
+    assert_eq!(fitnesses, vec![40., 50.]);
+    // A small subpopulation does not get filled with feasible individuals
+    assert_eq!(subpopulations.infeasible.len(), 2);
+
+    let population = subpopulations.into_population();
+    assert_eq!(population.len(), 4);
+    assert_eq!(population.iter().filter(|i| i.feasible).count(), 2);
+}
+
+#[test]
+fn test_parent_selection_on_tiny_population() {
+    let config = Config::new("config/config_test.yaml");
+    let mut rng = new_rng(Some(1));
+
+    assert_eq!(parent_selection(&[individual(1, 10., true)], &mut rng, &config, &linear_ranking), vec![0, 0]);
+    assert!(parent_selection(&[], &mut rng, &config, &linear_ranking).is_empty());
+}
+
+#[test]
+fn test_run_keeps_subpopulation_limits() {
+    let info = instance(25, 0.7, 15);
+    let mut config = Config::new("config/config_test.yaml");
+    config.population_size = 20;
+    config.subpopulations = true;
+    config.feasible_population_size = 6;
+    config.infeasible_population_size = 4;
+    let operators = OperatorRegistry::default().resolve(&config).unwrap();
+    let mut evolution = Evolution::new(&info, &config, &operators, new_rng(Some(15)));
+
+    for _ in 0..5 {
+        evolution.sort();
+        evolution.generation(None);
+        let feasible = evolution.population.iter().filter(|i| i.feasible).count();
+        assert!(feasible <= 6);
+        assert!(evolution.population.len() - feasible <= 4);
+        assert!(evolution.population.len() >= 2);
+    }
+}
@@ -0,0 +1,99 @@
+#[cfg(test)]
+use rand::seq::SliceRandom;
+#[cfg(test)]
+use rand::Rng;
+#[cfg(test)]
+use crate::genetic::evaluate::fitness_nurse;
+#[cfg(test)]
+use crate::genetic::random::new_rng;
+#[cfg(test)]
+use crate::genetic::route_cache::RouteCache;
+#[cfg(test)]
+use crate::structs::config::{Config, PenaltyModel};
+#[cfg(test)]
+use crate::structs::io::Info;
+#[cfg(test)]
+use crate::structs::nurse::Nurse;
+#[cfg(test)]
+use crate::test::fixtures::instance;
+
+#[cfg(test)]
+fn cost(route: Vec<i32>, info: &Info, config: &Config) -> f32 {
+    fitness_nurse(&Nurse { capacity: 0, route }, info, config).0
+}
+
+#[test]
+fn test_route_cache_matches_full_evaluation() {
+    let mut config = Config::new("config/config_test.yaml");
+    check_route_cache(&config);
+    config.penalty_model = PenaltyModel::TimeWarp;
+    check_route_cache(&config);
+}
+
+#[cfg(test)]
+fn check_route_cache(config: &Config) {
+    let info = instance(30, 0.3, 5);
+    let mut rng = new_rng(Some(5));
+    let mut feasible_routes = 0;
+
+    for _ in 0..300 {
+        let mut patients: Vec<i32> = (0..30).collect();
+        patients.shuffle(&mut rng);
+        let len = rng.random_range(0..6);
+        let mut route: Vec<i32> = patients[..len].to_vec();
+        // Sorting by time window gives mostly feasible routes
+        if rng.random_bool(0.7) {
+            route.sort_by_key(|p| info.patients[*p as usize].start_time);
+        }
+        let cache = RouteCache::new(&route, &info, config);
+        let base = cost(route.clone(), &info, config);
+        assert!((cache.cost - base).abs() < 1e-3);
+        feasible_routes += cache.feasible as usize;
+
+        let start = rng.random_range(0..=len);
+        let end = rng.random_range(start..=len);
+        let replacement: Vec<i32> = patients[len..len + rng.random_range(0..3)].to_vec();
+        let mut changed = route.clone();
+        changed.splice(start..end, replacement.clone());
+        let expected = cost(changed, &info, config) - base;
+        let delta = cache.splice_delta(&route, start, end, &replacement, &info, config);
+        assert!((delta - expected).abs() < 1e-2, "route {:?} splice {}..{} {:?}: {} vs {}", route, start, end, replacement, delta, expected);
+
+        if len >= 2 {
+            let (i, j) = (rng.random_range(0..len), rng.random_range(0..len));
+            let mut swapped = route.clone();
+            swapped.swap(i, j);
+            let expected = cost(swapped, &info, config) - base;
+            assert!((cache.swap_delta(&route, i, j, &info, config) - expected).abs() < 1e-2);
+        }
+    }
+    assert!(feasible_routes > 50);
+}
+
+#[test]
+fn test_swap_delta_on_long_routes() {
+    let info = instance(40, 0.3, 11);
+    let mut config = Config::new("config/config_test.yaml");
+    let mut rng = new_rng(Some(11));
+
+    for model in [PenaltyModel::Legacy, PenaltyModel::TimeWarp] {
+        config.penalty_model = model;
+        for len in [7, 16, 29] {
+            let mut route: Vec<i32> = (0..40).collect();
+            route.shuffle(&mut rng);
+            route.truncate(len);
+            route.sort_by_key(|p| info.patients[*p as usize].start_time);
+            let cache = RouteCache::new(&route, &info, &config);
+            let base = cost(route.clone(), &info, &config);
+
+            for i in 0..len {
+                for j in i..len {
+                    let mut swapped = route.clone();
+                    swapped.swap(i, j);
+                    let expected = cost(swapped, &info, &config) - base;
+                    assert!((cache.swap_delta(&route, i, j, &info, &config) - expected).abs() < 1e-2);
+                }
+            }
+        }
+    }
+}
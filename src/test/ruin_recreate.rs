@@ -0,0 +1,62 @@
+#[cfg(test)]
+use crate::genetic::random::new_rng;
+#[cfg(test)]
+use crate::genetic::ruin_recreate::{recreate, ruin};
+#[cfg(test)]
+use crate::structs::config::{Config, Insertion, Removal};
+#[cfg(test)]
+use crate::structs::nurse::Nurse;
+#[cfg(test)]
+use crate::test::fixtures::{assert_visits_every_patient_once, initial_individual, instance};
+
+#[test]
+fn test_ruin_and_recreate_keeps_every_patient_once() {
+    let info = instance(40, 0.5, 25);
+    let config = Config::new("config/config_test.yaml");
+    let mut rng = new_rng(Some(25));
+    let individual = initial_individual(&info, &config, &mut rng);
+
+    for removal in [Removal::Random, Removal::Worst, Removal::Shaw, Removal::Route, Removal::String] {
+        for insertion in [Insertion::Greedy, Insertion::Regret, Insertion::Blink] {
+            let mut nurses = individual.nurses.clone();
+            let removed = ruin(&mut nurses, removal, 8, &mut rng, &info, &config);
+            let left: usize = nurses.iter().map(|nurse| nurse.route.len()).sum();
+            assert_eq!(left + removed.len(), info.patients.len());
+            if removal == Removal::Route {
+                assert!(removed.len() >= 8);
+            } else {
+                assert_eq!(removed.len(), 8, "{:?}", removal);
+            }
+
+            recreate(&mut nurses, removed, insertion, &mut rng, &info, &config);
+            assert_visits_every_patient_once(&nurses, &info);
+        }
+    }
+}
+
+#[test]
+fn test_string_removal_removes_count() {
+    let info = instance(40, 0.5, 26);
+    let mut config = Config::new("config/config_test.yaml");
+    config.sisr_max_string_length = 2;
+    let mut rng = new_rng(Some(26));
+    let individual = initial_individual(&info, &config, &mut rng);
+    let routes = individual.nurses.iter().filter(|nurse| !nurse.route.is_empty()).count();
+
+    // More than one string of at most two patients per route can remove
+    let count = (2 * routes + 3).min(info.patients.len());
+    let mut nurses = individual.nurses.clone();
+    assert_eq!(ruin(&mut nurses, Removal::String, count, &mut rng, &info, &config).len(), count);
+}
+
+#[test]
+fn test_ruin_without_patients() {
+    let info = instance(10, 0.5, 27);
+    let config = Config::new("config/config_test.yaml");
+    let mut rng = new_rng(Some(27));
+
+    for removal in [Removal::Random, Removal::Worst, Removal::Shaw, Removal::Route, Removal::String] {
+        let mut nurses = vec![Nurse::new(); 3];
+        assert!(ruin(&mut nurses, removal, 4, &mut rng, &info, &config).is_empty());
+    }
+}
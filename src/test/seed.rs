@@ -0,0 +1,35 @@
+#[cfg(test)]
+use crate::structs::config::Config;
+#[cfg(test)]
+use crate::structs::io;
+#[cfg(test)]
+use crate::{SolveResult, Solver};
+
+#[cfg(test)]
+fn solve(overrides: &[&str]) -> SolveResult {
+    let mut overrides: Vec<String> = overrides.iter().map(|o| o.to_string()).collect();
+    overrides.extend(["population_size=20", "n_generations=6", "log_frequency=100", "run_time=-1", "seed=42"].map(String::from));
+    let config = Config::from_file("config/config_test.yaml", &overrides).unwrap();
+    let info = io::read_from_json(&config).unwrap();
+    Solver::builder(info, config).islands(2).build().unwrap().solve()
+}
+
+#[test]
+fn test_seeded_runs_are_reproducible() {
+    // Override sets, each with the number of generations the run must make
+    let runs: [(&[&str], usize); 6] = [
+        (&["use_islands=false"], 6),
+        (&["use_islands=true", "island_share_frequency=2"], 12),
+        (&["use_islands=true", "island_share_frequency=2", "penalty_model=TimeWarp", "adaptive_penalties=true", "penalty_adjust_frequency=2"], 12),
+        (&["use_islands=false", "subpopulations=true", "feasible_population_size=8", "infeasible_population_size=8"], 6),
+        (&["use_islands=false", "subpopulations=true", "feasible_population_size=8", "infeasible_population_size=8", "biased_fitness=true"], 6),
+        (&["use_islands=false", "education=true", "education_max_moves=20"], 6),
+    ];
+    for (overrides, generations) in runs {
+        let first = solve(overrides);
+        let second = solve(overrides);
+        assert_eq!(first.best, second.best, "{:?}", overrides);
+        assert_eq!(first.best.fitness, second.best.fitness, "{:?}", overrides);
+        assert_eq!(first.stats.generations, generations, "{:?}", overrides);
+    }
+}
@@ -0,0 +1,103 @@
+#[cfg(test)]
+use rand::seq::SliceRandom;
+#[cfg(test)]
+use rand::Rng;
+#[cfg(test)]
+use crate::genetic::evaluate::{fitness_nurse, is_feasible_fitness_nurse, Segment};
+#[cfg(test)]
+use crate::structs::config::{Config, PenaltyModel};
+#[cfg(test)]
+use crate::genetic::random::new_rng;
+#[cfg(test)]
+use crate::structs::io::{Depot, Info, InfoRaw, Patient};
+#[cfg(test)]
+use crate::structs::nurse::Nurse;
+#[cfg(test)]
+use crate::structs::travel_times::DistanceMetric;
+#[cfg(test)]
+use crate::test::fixtures::instance;
+
+#[cfg(test)]
+fn line_instance() -> Info {
+    // Depot at 0, patients at 10 and 20 on a line, care time 5
+    let patient = |x: f64, start_time: u32, end_time: u32| Patient {
+        x_coord: x, y_coord: 0., demand: 10, start_time, end_time, care_time: 5,
+    };
+    Info::try_from(InfoRaw {
+        instance_name: "line".to_string(),
+        nbr_nurses: 1,
+        capacity_nurse: 15,
+        benchmark: 0.,
+        depot: Depot { return_time: 100, x_coord: 0., y_coord: 0. },
+        travel_times: None,
+        distance_metric: DistanceMetric::Euclidean,
+        patients: vec![patient(10., 30, 50), patient(20., 0, 25)],
+    }).unwrap()
+}
+
+#[test]
+fn test_segment_time_warp() {
+    let info = line_instance();
+
+    // Patient 1 can start at 30 at the earliest, so care of patient 2 ends at 30 + 5 + 10 + 5 = 50, 25 late
+    let route = Segment::of_route(&[0, 1], &info);
+    assert_eq!(route.distance, 40.);
+    assert_eq!(route.time_warp, 25.);
+    assert_eq!(route.load, 20);
+    assert!(!route.is_feasible(&info));
+
+    // The other way round is on time
+    let route = Segment::of_route(&[1, 0], &info);
+    assert_eq!(route.time_warp, 0.);
+    assert_eq!(route.duration, 20. + 5. + 10. + 5. + 10.);
+    assert!(!route.is_feasible(&info), "demand 20 is over the capacity of 15");
+}
+
+#[test]
+fn test_segment_matches_route_feasibility() {
+    let info = instance(25, 0.4, 8);
+    let mut rng = new_rng(Some(8));
+    let mut feasible = 0;
+
+    for _ in 0..300 {
+        let mut route: Vec<i32> = (0..25).collect();
+        route.shuffle(&mut rng);
+        route.truncate(rng.random_range(1..8));
+        if rng.random_bool(0.7) {
+            route.sort_by_key(|p| info.patients[*p as usize].start_time);
+        }
+
+        let segment = Segment::of_route(&route, &info);
+        let expected = is_feasible_fitness_nurse(&Nurse { capacity: 0, route: route.clone() }, &info);
+        assert_eq!(segment.is_feasible(&info), expected, "{:?}", route);
+        feasible += expected as usize;
+
+        // Concatenation is associative
+        let split = rng.random_range(0..route.len());
+        let (head, tail) = route.split_at(split);
+        let depot = Segment::depot(&info);
+        let joined = match (Segment::of_patients(head, &info), Segment::of_patients(tail, &info)) {
+            (Some(head), Some(tail)) => depot.concat(&head, &info).concat(&tail.concat(&depot, &info), &info),
+            (None, Some(tail)) => depot.concat(&tail.concat(&depot, &info), &info),
+            _ => unreachable!(),
+        };
+        assert!((joined.distance - segment.distance).abs() < 1e-3);
+        assert!((joined.time_warp - segment.time_warp).abs() < 1e-2);
+    }
+    assert!(feasible > 30);
+}
+
+#[test]
+fn test_time_warp_penalty() {
+    let info = line_instance();
+    let mut config = Config::new("config/config_test.yaml");
+    config.penalty_model = PenaltyModel::TimeWarp;
+    config.time_warp_penalty = 2.;
+    config.load_penalty = 3.;
+
+    // 40 travel, 25 time warp, 5 demand over capacity
+    let (fitness, feasible) = fitness_nurse(&Nurse { capacity: 0, route: vec![0, 1] }, &info, &config);
+    assert_eq!(fitness, 40. + 2. * 25. + 3. * 5.);
+    assert!(!feasible);
+    assert_eq!(fitness_nurse(&Nurse { capacity: 0, route: vec![1] }, &info, &config), (40., true));
+}
@@ -0,0 +1,65 @@
+#[cfg(test)]
+use crate::structs::solomon::parse_solomon;
+
+#[cfg(test)]
+const C101_HEAD: &str = "C101
+
+VEHICLE
+NUMBER     CAPACITY
+  25         200
+
+CUSTOMER
+CUST NO.  XCOORD.   YCOORD.    DEMAND   READY TIME  DUE DATE   SERVICE   TIME
+
+    0      40         50          0          0       1236          0
+    1      45         68         10        912        967         90
+    2      45         70         30        825        870         90
+    3      42         66         10         65        146         90
+";
+
+#[test]
+fn test_parse_solomon() {
+    let info = parse_solomon(C101_HEAD).unwrap();
+    assert_eq!(info.instance_name, "C101");
+    assert_eq!(info.nbr_nurses, 25);
+    assert_eq!(info.capacity_nurse, 200);
+    assert_eq!(info.depot.return_time, 1236);
+    assert_eq!(info.patients.len(), 3);
+
+    let patient = info.patients[0];
+    assert_eq!((patient.x_coord, patient.y_coord), (45., 68.));
+    assert_eq!((patient.start_time, patient.end_time, patient.care_time), (912, 967 + 90, 90));
+
+    assert_eq!(info.travel_times.len(), 4);
+    assert!((info.travel_times[0][1] - 18.681542).abs() < 1e-4);
+    assert_eq!(info.travel_times[2][2], 0.);
+    assert_eq!(info.travel_times_sorted[1][0], 2);
+}
+
+#[test]
+fn test_parse_solomon_errors() {
+    assert!(parse_solomon("").is_err());
+    assert!(parse_solomon(&C101_HEAD.replace("  25         200", "25")).is_err());
+    assert!(parse_solomon(&C101_HEAD.replace("    2      45         70", "    1      45         70")).is_err());
+}
+
+#[test]
+fn test_large_instance_neighbours() {
+    use crate::structs::io::NEIGHBOUR_LIST_SIZE;
+
+    let mut content = String::from("RC1_10_1\n\nVEHICLE\nNUMBER     CAPACITY\n 250        200\n\nCUSTOMER\n");
+    content.push_str("CUST NO.  XCOORD.   YCOORD.    DEMAND   READY TIME  DUE DATE   SERVICE   TIME\n\n");
+    for cust in 0..=1000 {
+        content.push_str(&format!("{} {} {} 10 0 5000 10\n", cust, cust % 37, cust / 37));
+    }
+    let info = parse_solomon(&content).unwrap();
+    assert_eq!(info.patients.len(), 1000);
+    assert_eq!(info.travel_times.len(), 1001);
+    assert!(info.travel_times_sorted.iter().all(|neighbours| neighbours.len() == NEIGHBOUR_LIST_SIZE));
+
+    let neighbours = &info.travel_times_sorted[500];
+    assert!(!neighbours.contains(&500));
+    assert!(neighbours.windows(2).all(|pair| info.travel_times[500][pair[0]] <= info.travel_times[500][pair[1]]));
+    let furthest_kept = info.travel_times[500][*neighbours.last().unwrap()];
+    assert!((0..1001).filter(|n| *n != 500 && !neighbours.contains(n)).all(|n| info.travel_times[500][n] >= furthest_kept));
+}
@@ -0,0 +1,68 @@
+#[cfg(test)]
+use std::fs;
+#[cfg(test)]
+use crate::genetic::evaluate::travel_time;
+#[cfg(test)]
+use crate::structs::config::Config;
+#[cfg(test)]
+use crate::structs::nurse::{Individual, Nurse};
+#[cfg(test)]
+use crate::test::fixtures::instance;
+#[cfg(test)]
+use crate::util::save_individual::{best_individual_file, individual_folder, read_individual, save_individual, SolutionFile};
+
+#[test]
+fn test_save_and_read_solution_file() {
+    let info = instance(4, 0.5, 1);
+    let mut config = Config::new("config/config_test.yaml");
+    config.output_dir = std::env::temp_dir().join(format!("solution_file_test_{}", std::process::id())).to_string_lossy().to_string();
+    config.seed = Some(9);
+
+    let individual = Individual {
+        nurses: vec![Nurse { capacity: 0, route: vec![2, 0] }, Nurse { capacity: 0, route: vec![] }, Nurse { capacity: 0, route: vec![1, 3] }],
+        fitness: 321.5,
+        feasible: true,
+    };
+    save_individual(&individual, &info, &config, "run-1");
+
+    // Legacy files hold only the routes
+    let folder = individual_folder(&config);
+    fs::write(folder.clone() + "400.25", "[[1, 2], [], [3, 4]]").unwrap();
+    assert_eq!(read_individual(&std::path::Path::new(&folder).join("400.25")).unwrap(), vec![vec![1, 2], vec![], vec![3, 4]]);
+
+    let best = best_individual_file(&folder).unwrap();
+    assert!(best.ends_with("321.5.json"));
+    assert_eq!(read_individual(&best).unwrap(), vec![vec![3, 1], vec![], vec![2, 4]]);
+
+    let solution: SolutionFile = serde_json::from_str(&fs::read_to_string(&best).unwrap()).unwrap();
+    assert_eq!((solution.run_id.as_str(), solution.instance_name.as_str()), ("run-1", "generated"));
+    assert_eq!((solution.objective, solution.feasible, solution.seed), (321.5, true, Some(9)));
+    assert_eq!(solution.config["file_name"], config.file_name.as_str());
+
+    let route = &solution.routes[0];
+    assert_eq!(route.arrival_times[0], info.travel_times[0][3]);
+    assert!(route.departure_times[0] >= route.arrival_times[0] + info.patients[2].care_time as f32);
+    assert_eq!(route.depot_return, route.departure_times[1] + travel_time(1, 0, &info));
+    assert_eq!(solution.routes[1].depot_return, 0.);
+
+    fs::remove_dir_all(&config.output_dir).unwrap();
+}
+
+#[test]
+fn test_read_solution_file_with_old_config() {
+    let dir = std::env::temp_dir().join(format!("old_solution_file_test_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let route = r#"{"patients": [2, 1], "arrival_times": [5.0, 9.0], "departure_times": [7.0, 11.0], "depot_return": 14.0}"#;
+    let header = r#""run_id": "old", "instance_name": "train_0", "timestamp": 0, "objective": 14.0, "feasible": true, "seed": null"#;
+
+    // A config from before later fields were added, and a file saved without any config
+    let partial = dir.join("14.json");
+    fs::write(&partial, format!(r#"{{{}, "routes": [{}], "config": {{"file_name": "train_0"}}}}"#, header, route)).unwrap();
+    let missing = dir.join("15.json");
+    fs::write(&missing, format!(r#"{{{}, "routes": [{}]}}"#, header, route)).unwrap();
+
+    assert_eq!(read_individual(&partial).unwrap(), vec![vec![2, 1]]);
+    assert_eq!(read_individual(&missing).unwrap(), vec![vec![2, 1]]);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,98 @@
+#[cfg(test)]
+use rand::seq::SliceRandom;
+#[cfg(test)]
+use crate::genetic::evaluate::{penalized_cost, Segment};
+#[cfg(test)]
+use crate::genetic::random::new_rng;
+#[cfg(test)]
+use crate::genetic::split::{from_giant_tour, giant_tour, split};
+#[cfg(test)]
+use crate::structs::config::{Config, PenaltyModel};
+#[cfg(test)]
+use crate::structs::io::Info;
+#[cfg(test)]
+use crate::structs::nurse::Nurse;
+#[cfg(test)]
+use crate::test::fixtures::{initial_individual, instance};
+
+#[cfg(test)]
+fn split_cost(nurses: &[Nurse], info: &Info, config: &Config) -> f32 {
+    nurses.iter().map(|nurse| penalized_cost(&Segment::of_route(&nurse.route, info), info, config)).sum()
+}
+
+#[test]
+fn test_split_is_no_worse_than_the_routes_of_the_tour() {
+    let mut config = Config::new("config/config_test.yaml");
+    config.penalty_model = PenaltyModel::TimeWarp;
+    let info = instance(40, 0.4, 9);
+    let mut rng = new_rng(Some(9));
+
+    for _ in 0..10 {
+        let individual = initial_individual(&info, &config, &mut rng);
+        let tour = giant_tour(&individual);
+        let nurses = split(&tour, &info, &config);
+
+        assert_eq!(nurses.len(), info.nbr_nurses as usize);
+        let patients: Vec<i32> = nurses.iter().flat_map(|nurse| nurse.route.clone()).collect();
+        assert_eq!(patients, tour);
+        // The routes of the individual are one of the splits of its giant tour
+        assert!(split_cost(&nurses, &info, &config) <= split_cost(&individual.nurses, &info, &config) + 1e-3);
+    }
+}
+
+#[test]
+fn test_split_is_optimal() {
+    let mut config = Config::new("config/config_test.yaml");
+    config.time_warp_penalty = 1.;
+    let mut rng = new_rng(Some(4));
+
+    for seed in 0..4 {
+        let mut info = instance(9, 0.6, seed);
+        let total_demand: u32 = info.patients.iter().map(|patient| patient.demand).sum();
+        info.capacity_nurse = total_demand / 4;
+        let mut tour: Vec<i32> = (0..9).collect();
+        tour.shuffle(&mut rng);
+
+        // A low load penalty makes routes far over the capacity worth it
+        for (nbr_nurses, load_penalty) in [(2, 10.), (3, 0.01), (3, 0.5), (3, 10.), (5, 0.5)] {
+            info.nbr_nurses = nbr_nurses;
+            config.load_penalty = load_penalty;
+            let cost = split_cost(&split(&tour, &info, &config), &info, &config);
+            let best = brute_force_split_cost(&tour, &info, &config);
+            assert!((cost - best).abs() < 1e-3, "split costs {} but the best split costs {}", cost, best);
+        }
+    }
+}
+
+/// Cost of the cheapest split, trying every set of at most `nbr_nurses - 1` cuts between the patients
+#[cfg(test)]
+fn brute_force_split_cost(tour: &[i32], info: &Info, config: &Config) -> f32 {
+    (0..1u32 << (tour.len() - 1))
+        .filter(|cuts| cuts.count_ones() < info.nbr_nurses)
+        .map(|cuts| {
+            let mut nurses = vec![Nurse::new()];
+            for (position, &patient) in tour.iter().enumerate() {
+                if position > 0 && cuts & 1 << (position - 1) != 0 {
+                    nurses.push(Nurse::new());
+                }
+                nurses.last_mut().unwrap().route.push(patient);
+            }
+            split_cost(&nurses, info, config)
+        })
+        .fold(f32::INFINITY, f32::min)
+}
+
+#[test]
+fn test_split_with_too_few_nurses() {
+    let config = Config::new("config/config_test.yaml");
+    let mut info = instance(20, 0.5, 3);
+    info.nbr_nurses = 2;
+    info.capacity_nurse = 1;
+
+    let tour: Vec<i32> = (0..20).collect();
+    let individual = from_giant_tour(&tour, &info, &config);
+    assert_eq!(individual.nurses.len(), 2);
+    assert_eq!(giant_tour(&individual), tour);
+    assert!(!individual.feasible);
+    assert!(split(&[], &info, &config).iter().all(|nurse| nurse.route.is_empty()));
+}
@@ -0,0 +1,82 @@
+#[cfg(test)]
+use crate::genetic::evaluate::{is_feasible_fitness_nurse, Segment};
+#[cfg(test)]
+use crate::structs::io::{Depot, Info, InfoRaw, Patient};
+#[cfg(test)]
+use crate::structs::nurse::Nurse;
+#[cfg(test)]
+use crate::structs::travel_times::{DistanceMetric, TravelTimes};
+#[cfg(test)]
+use crate::util::validate::{validate_solution, Violation};
+
+#[cfg(test)]
+fn line_instance() -> Info {
+    // Depot at 0, patients at 10, 20 and 30 on a line
+    let patient = |x: f64, start_time: u32, end_time: u32| Patient {
+        x_coord: x, y_coord: 0., demand: 10, start_time, end_time, care_time: 5,
+    };
+    Info::try_from(InfoRaw {
+        instance_name: "line".to_string(),
+        nbr_nurses: 2,
+        capacity_nurse: 25,
+        benchmark: 0.,
+        depot: Depot { return_time: 70, x_coord: 0., y_coord: 0. },
+        travel_times: None,
+        distance_metric: DistanceMetric::Euclidean,
+        patients: vec![patient(10., 0, 100), patient(20., 0, 100), patient(30., 0, 40)],
+    }).unwrap()
+}
+
+#[test]
+fn test_valid_solution() {
+    let report = validate_solution(&[vec![3, 2], vec![1]], &line_instance());
+    assert!(report.is_valid(), "{:?}", report.violations);
+    assert_eq!(report.nurses_used, 2);
+    assert_eq!(report.total_travel_time, 60. + 20.);
+}
+
+#[test]
+fn test_violations() {
+    let report = validate_solution(&[vec![1, 2, 3, 3, 7]], &line_instance());
+    assert!(!report.is_valid());
+    assert_eq!(report.total_travel_time, 60.);
+    assert!(report.violations.contains(&Violation::UnknownPatient { nurse: 0, patient: 7 }));
+    assert!(report.violations.contains(&Violation::DuplicatedPatient { patient: 3, visits: 2 }));
+    assert!(report.violations.contains(&Violation::CapacityExceeded { nurse: 0, demand: 40, capacity: 25 }));
+    // 10 + 5 + 10 + 5 + 10 + 5 = 45, window ends at 40
+    assert!(report.violations.contains(&Violation::LateCareFinish {
+        nurse: 0, patient: 3, care_finished: 45., end_time: 40, lateness: 5.,
+    }));
+    assert!(report.violations.contains(&Violation::LateDepotReturn {
+        nurse: 0, arrival: 80., return_time: 70, lateness: 10.,
+    }));
+
+    let report = validate_solution(&[vec![1], vec![], vec![2]], &line_instance());
+    assert!(report.violations.contains(&Violation::MissingPatient { patient: 3 }));
+    // The empty route does not count
+    assert_eq!(report.nurses_used, 2);
+    assert!(!report.violations.iter().any(|violation| matches!(violation, Violation::TooManyNurses { .. })));
+
+    let report = validate_solution(&[vec![1], vec![], vec![2], vec![3]], &line_instance());
+    assert!(report.violations.contains(&Violation::TooManyNurses { used: 3, available: 2 }));
+}
+
+#[test]
+fn test_asymmetric_travel_times() {
+    // 10 from the depot to the patient and 30 back, read by the solver as travel_times[0][1]
+    let info = Info::try_from(InfoRaw {
+        instance_name: "asymmetric".to_string(),
+        nbr_nurses: 1,
+        capacity_nurse: 25,
+        benchmark: 0.,
+        depot: Depot { return_time: 30, x_coord: 0., y_coord: 0. },
+        travel_times: Some(TravelTimes::try_from(vec![vec![0., 10.], vec![30., 0.]]).unwrap()),
+        distance_metric: DistanceMetric::Euclidean,
+        patients: vec![Patient { x_coord: 0., y_coord: 0., demand: 10, start_time: 0, end_time: 100, care_time: 5 }],
+    }).unwrap();
+
+    let report = validate_solution(&[vec![1]], &info);
+    assert!(report.is_valid(), "{:?}", report.violations);
+    assert!(is_feasible_fitness_nurse(&Nurse { capacity: 0, route: vec![0] }, &info));
+    assert_eq!(report.total_travel_time, Segment::of_route(&[0], &info).distance);
+}
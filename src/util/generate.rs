@@ -0,0 +1,177 @@
+use std::error::Error;
+use std::f64::consts::PI;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+use clap::ValueEnum;
+use rand::Rng;
+
+use crate::genetic::random::{new_rng, GaRng};
+use crate::structs::io::{Depot, InfoRaw, Patient};
+use crate::structs::travel_times::DistanceMetric;
+
+/// Spread of the patients, like the Solomon R, C and RC classes
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum Layout {
+    Random,
+    Clustered,
+    /// Half of the patients clustered, the rest spread at random
+    Mixed,
+}
+
+#[derive(Debug, Clone)]
+pub struct GeneratorOptions {
+    pub instance_name: String,
+    pub patients: usize,
+    pub layout: Layout,
+    /// Number of cluster centres for the clustered and mixed layouts
+    pub clusters: usize,
+    /// Patients are placed in a square of this size, with the depot in the middle
+    pub grid_size: f64,
+    /// Depot return time. Every time window lies inside it
+    pub horizon: u32,
+    /// 0 gives time windows spanning the whole day, 1 leaves no slack beyond the care time
+    pub time_window_tightness: f64,
+    pub care_time: (u32, u32),
+    pub demand: (u32, u32),
+    pub capacity: u32,
+    /// Estimated from the total demand when not set
+    pub nurses: Option<u32>,
+    pub seed: Option<u64>,
+}
+
+impl Default for GeneratorOptions {
+    fn default() -> Self {
+        GeneratorOptions {
+            instance_name: "generated".to_string(),
+            patients: 100,
+            layout: Layout::Random,
+            clusters: 8,
+            grid_size: 100.,
+            horizon: 1000,
+            time_window_tightness: 0.5,
+            care_time: (10, 10),
+            demand: (1, 30),
+            capacity: 200,
+            nurses: None,
+            seed: None,
+        }
+    }
+}
+
+/// Generate a coordinates-only instance. Every patient can be served on its own by one nurse,
+/// so the instance always has a feasible solution when there are enough nurses.
+pub fn generate_instance(options: &GeneratorOptions) -> Result<InfoRaw, Box<dyn Error>> {
+    if options.patients == 0 {
+        return Err("Generated instance needs at least one patient".into());
+    }
+    if !(0.0..=1.0).contains(&options.time_window_tightness) {
+        return Err(format!("Time window tightness must be between 0 and 1, got {}", options.time_window_tightness).into());
+    }
+    if options.care_time.0 > options.care_time.1 || options.demand.0 > options.demand.1 {
+        return Err("Care time and demand ranges need min <= max".into());
+    }
+    if !(options.grid_size.is_finite() && options.grid_size > 0.) {
+        return Err(format!("Grid size must be a positive number, got {}", options.grid_size).into());
+    }
+    if options.demand.1 > options.capacity {
+        return Err(format!("Demand up to {} does not fit a nurse capacity of {}", options.demand.1, options.capacity).into());
+    }
+
+    let mut rng = new_rng(options.seed);
+    let size = options.grid_size;
+    let depot = Depot { return_time: options.horizon, x_coord: size / 2., y_coord: size / 2. };
+    let centres: Vec<(f64, f64)> = (0..options.clusters.max(1))
+        .map(|_| (rng.random_range(0.1 * size..=0.9 * size), rng.random_range(0.1 * size..=0.9 * size)))
+        .collect();
+
+    let mut patients = Vec::with_capacity(options.patients);
+    for patient_idx in 0..options.patients {
+        let clustered = match options.layout {
+            Layout::Random => false,
+            Layout::Clustered => true,
+            Layout::Mixed => patient_idx % 2 == 0,
+        };
+        let (x_coord, y_coord) = if clustered {
+            let centre = centres[rng.random_range(0..centres.len())];
+            let (dx, dy) = normal_pair(&mut rng, 0.05 * size);
+            ((centre.0 + dx).clamp(0., size).round(), (centre.1 + dy).clamp(0., size).round())
+        } else {
+            (rng.random_range(0.0..=size).round(), rng.random_range(0.0..=size).round())
+        };
+        let care_time = rng.random_range(options.care_time.0..=options.care_time.1);
+        let demand = rng.random_range(options.demand.0..=options.demand.1);
+
+        // The window fits between driving straight from and straight back to the depot
+        let to_depot = DistanceMetric::Euclidean.distance((x_coord, y_coord), (depot.x_coord, depot.y_coord)).ceil();
+        let slack = options.horizon as f64 - 2. * to_depot - care_time as f64;
+        if slack < 0. {
+            return Err(format!("Horizon {} is too short for a grid of size {}", options.horizon, size).into());
+        }
+        let window_slack = ((1. - options.time_window_tightness) * slack).round();
+        let start_time = to_depot + rng.random_range(0.0..=slack - window_slack).floor();
+
+        patients.push(Patient {
+            x_coord,
+            y_coord,
+            demand,
+            start_time: start_time as u32,
+            end_time: (start_time + window_slack) as u32 + care_time,
+            care_time,
+        });
+    }
+
+    let total_demand: u32 = patients.iter().map(|patient| patient.demand).sum();
+    let min_nurses = total_demand.div_ceil(options.capacity).max(1);
+    let nbr_nurses = options.nurses.unwrap_or(min_nurses + min_nurses / 2 + 1);
+
+    let mut raw = InfoRaw {
+        instance_name: options.instance_name.clone(),
+        nbr_nurses,
+        capacity_nurse: options.capacity,
+        benchmark: 0.,
+        depot,
+        travel_times: None,
+        distance_metric: DistanceMetric::Euclidean,
+        patients,
+    };
+    raw.benchmark = estimate_benchmark(&raw, min_nurses, options.time_window_tightness);
+    Ok(raw)
+}
+
+/// Rough estimate of a good solution: the Beardwood-Halton-Hammersley tour length through all
+/// patients plus the trips to and from the depot, scaled up for tight time windows. Only meant as a
+/// placeholder so the solver has something to save solutions against.
+fn estimate_benchmark(raw: &InfoRaw, min_nurses: u32, tightness: f64) -> f32 {
+    let coordinates = raw.coordinates();
+    let (min_x, max_x) = coordinates.iter().fold((f64::MAX, f64::MIN), |(lo, hi), c| (lo.min(c.0), hi.max(c.0)));
+    let (min_y, max_y) = coordinates.iter().fold((f64::MAX, f64::MIN), |(lo, hi), c| (lo.min(c.1), hi.max(c.1)));
+    let area = ((max_x - min_x) * (max_y - min_y)).max(1.);
+
+    let tour = 0.7124 * (raw.patients.len() as f64 * area).sqrt();
+    let mean_depot_distance = coordinates[1..]
+        .iter()
+        .map(|c| DistanceMetric::Euclidean.distance(*c, coordinates[0]))
+        .sum::<f64>() / raw.patients.len() as f64;
+    let radial = 2. * mean_depot_distance * min_nurses as f64;
+
+    ((tour + radial) * (1. + tightness)).round() as f32
+}
+
+fn normal_pair(rng: &mut GaRng, std_dev: f64) -> (f64, f64) {
+    // Box-Muller transform
+    let u1: f64 = rng.random_range(f64::EPSILON..1.);
+    let u2: f64 = rng.random();
+    let radius = (-2. * u1.ln()).sqrt() * std_dev;
+    (radius * (2. * PI * u2).cos(), radius * (2. * PI * u2).sin())
+}
+
+pub fn write_instance(raw: &InfoRaw, path: &Path) -> Result<(), Box<dyn Error>> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    let file = File::create(path).map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+    serde_json::to_writer_pretty(BufWriter::new(file), raw)?;
+    Ok(())
+}
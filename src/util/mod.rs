@@ -1,3 +1,5 @@
 pub mod plot;
 pub mod save_individual;
-pub mod print;
\ No newline at end of file
+pub mod print;
+pub mod generate;
+pub mod validate;
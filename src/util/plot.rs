@@ -1,55 +1,55 @@
 use std::f64::consts::PI;
 use std::fs;
-
-use serde_json::from_str;
+use std::path::Path;
 
 use plotters::prelude::*;
 use plotters::coord::types::RangedCoordf64;
 use plotters::style::RGBColor;
 
 use crate::structs::config::Config;
-use crate::structs::io::{read_from_json, Info};
+use crate::structs::io::{read_instance, Info};
 use crate::util::print::print_best_solution;
+use crate::util::save_individual::{best_individual_file, individual_folder, read_individual};
 
 fn draw_arrow(
     chart: &mut ChartContext<BitMapBackend,
     Cartesian2d<RangedCoordf64, RangedCoordf64>>,
-    (x1, y1): (i32, i32),
-    (x2, y2): (i32, i32),
+    (x1, y1): (f64, f64),
+    (x2, y2): (f64, f64),
     color: &RGBColor
     ) -> Result<(), Box<dyn std::error::Error>> {
-    let angle = ((y2 - y1) as f64).atan2((x2 - x1) as f64);
+    let angle = (y2 - y1).atan2(x2 - x1);
     let arrowhead_length = 1.0;
 
     // Arrowhead points
-    let left_x = x2 as f64 - arrowhead_length * (angle + PI / 6.0).cos();
-    let left_y = y2 as f64 - arrowhead_length * (angle + PI / 6.0).sin();
-    let right_x = x2 as f64 - arrowhead_length * (angle - PI / 6.0).cos();
-    let right_y = y2 as f64 - arrowhead_length * (angle - PI / 6.0).sin();
+    let left_x = x2 - arrowhead_length * (angle + PI / 6.0).cos();
+    let left_y = y2 - arrowhead_length * (angle + PI / 6.0).sin();
+    let right_x = x2 - arrowhead_length * (angle - PI / 6.0).cos();
+    let right_y = y2 - arrowhead_length * (angle - PI / 6.0).sin();
 
-    chart.draw_series(LineSeries::new(vec![(x1 as f64, y1 as f64), (x2 as f64, y2 as f64)], color)).unwrap();
-    chart.draw_series(LineSeries::new(vec![(x2 as f64, y2 as f64), (left_x, left_y)], color)).unwrap();
-    chart.draw_series(LineSeries::new(vec![(x2 as f64, y2 as f64), (right_x, right_y)], color)).unwrap();
+    chart.draw_series(LineSeries::new(vec![(x1, y1), (x2, y2)], color)).unwrap();
+    chart.draw_series(LineSeries::new(vec![(x2, y2), (left_x, left_y)], color)).unwrap();
+    chart.draw_series(LineSeries::new(vec![(x2, y2), (right_x, right_y)], color)).unwrap();
 
     Ok(())
 }
-pub fn plot_points(individual: &Vec<Vec<i32>>, info: &Info) {
-    let file_name = "plots/".to_owned() + &*info.instance_name.clone() + ".png";
+pub fn plot_points(individual: &Vec<Vec<i32>>, info: &Info, plot_dir: &str) {
+    let file_name = plot_dir.to_owned() + "/" + &*info.instance_name.clone() + ".png";
     let root = BitMapBackend::new(&file_name, (1200, 1200)).into_drawing_area();
     root.fill(&WHITE).unwrap();
 
-    let mut min_x = f32::INFINITY;
-    let mut max_x = f32::NEG_INFINITY;
-    let mut min_y = f32::INFINITY;
-    let mut max_y = f32::NEG_INFINITY;
+    let mut min_x = f64::INFINITY;
+    let mut max_x = f64::NEG_INFINITY;
+    let mut min_y = f64::INFINITY;
+    let mut max_y = f64::NEG_INFINITY;
 
     for nurse in individual {
         for &patient in nurse {
             let p_info = &info.patients[patient as usize - 1];
-            min_x = min_x.min(p_info.x_coord as f32);
-            max_x = max_x.max(p_info.x_coord as f32);
-            min_y = min_y.min(p_info.y_coord as f32);
-            max_y = max_y.max(p_info.y_coord as f32);
+            min_x = min_x.min(p_info.x_coord);
+            max_x = max_x.max(p_info.x_coord);
+            min_y = min_y.min(p_info.y_coord);
+            max_y = max_y.max(p_info.y_coord);
         }
     }
 
@@ -65,8 +65,8 @@ pub fn plot_points(individual: &Vec<Vec<i32>>, info: &Info) {
         .x_label_area_size(40)
         .y_label_area_size(40)
         .build_cartesian_2d(
-            (min_x - padding_x) as f64..(max_x + padding_x) as f64,
-            (min_y - padding_y) as f64..(max_y + padding_y) as f64,
+            (min_x - padding_x)..(max_x + padding_x),
+            (min_y - padding_y)..(max_y + padding_y),
         )
         .unwrap();
 
@@ -84,9 +84,9 @@ pub fn plot_points(individual: &Vec<Vec<i32>>, info: &Info) {
             let p_info = info.patients[*patient as usize - 1];
             let _ = chart
                 .draw_series(std::iter::once(Circle::new(
-                    (p_info.x_coord as f64, p_info.y_coord as f64),
+                    (p_info.x_coord, p_info.y_coord),
                     5,
-                    &BLUE.mix(0.7),
+                    BLUE.mix(0.7),
                 )));
         }
     }
@@ -124,64 +124,56 @@ pub fn plot_points(individual: &Vec<Vec<i32>>, info: &Info) {
 
     root.present().unwrap();
 }
-pub fn plot_best_individual() {
-    let info = read_from_json(&Config::new("config/config.yaml")).unwrap();
-    let config = Config::new("./config/config.yaml");
-    let folder_path = "./individuals/".to_string() + &*config.file_name + "/";
-    fs::create_dir_all(&folder_path).unwrap();
-
-    // Find the file with the smallest numerical name
-    let mut min_file: f32 = f32::INFINITY;
-    let mut parsed_vec: Vec<Vec<i32>> = Vec::new();
-    if fs::read_dir(&folder_path).unwrap().next().is_none() {
-        return;
-    }
-
-    for entry in fs::read_dir(&folder_path).unwrap() {
-        let entry = entry.unwrap();
-        let filename = entry.file_name();
-        let file_num= filename.to_str().unwrap();
-        let file_num: f32 = file_num.parse::<f32>().unwrap();
-
-        if file_num <  min_file {
-            min_file = file_num;
-            let fp = format!("{}/{}", folder_path, &filename.to_str().unwrap());
-            let file_content = fs::read_to_string(&fp).unwrap();
-            parsed_vec = from_str(&file_content).unwrap();
+pub fn plot_best_individual(config: &Config, plot_dir: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let info = read_instance(config)?;
+    let folder_path = individual_folder(config);
+
+    let best_file = match best_individual_file(&folder_path) {
+        Some(best_file) => best_file,
+        None => {
+            println!("No saved individuals in {}", folder_path);
+            return Ok(());
         }
-    }
+    };
+    plot_individual_file(&best_file, &info, plot_dir)?;
+    println!("Made a plot for: {}", &*config.file_name);
+    Ok(())
+}
 
-    plot_points(&parsed_vec, &info);
-    print_best_solution(parsed_vec, &info);
-    println!("Made a plot for: {}", &*config.file_name)
+pub fn plot_individual_file(path: &Path, info: &Info, plot_dir: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let parsed_vec = read_individual(path)?;
+    fs::create_dir_all(plot_dir)?;
+    plot_points(&parsed_vec, info, plot_dir);
+    print_best_solution(parsed_vec, info);
+    Ok(())
 }
 
 fn generate_colors() -> Vec<RGBColor> {
     vec![
-        RGBColor { 0: 255, 1: 0, 2: 0 },       // Red
-        RGBColor { 0: 0, 1: 255, 2: 0 },       // Green
-        RGBColor { 0: 0, 1: 0, 2: 255 },       // Blue
-        RGBColor { 0: 255, 1: 255, 2: 0 },     // Yellow
-        RGBColor { 0: 255, 1: 0, 2: 255 },     // Ma1enta
-        RGBColor { 0: 0, 1: 255, 2: 255 },     // Cyan
-        RGBColor { 0: 128, 1: 0, 2: 0 },       // Maroon
-        RGBColor { 0: 0, 1: 128, 2: 0 },       // Dark Green
-        RGBColor { 0: 0, 1: 0, 2: 128 },       // Navy
-        RGBColor { 0: 128, 1: 128, 2: 0 },     // Olive
-        RGBColor { 0: 128, 1: 0, 2: 128 },     // Purple
-        RGBColor { 0: 0, 1: 128, 2: 128 },     // Teal
-        RGBColor { 0: 192, 1: 192, 2: 192 },   // Silver
-        RGBColor { 0: 128, 1: 128, 2: 128 },   // Gray
-        RGBColor { 0: 255, 1: 165, 2: 0 },     // Oran1e
-        RGBColor { 0: 255, 1: 192, 2: 203 },   // Pink
-        RGBColor { 0: 165, 1: 42, 2: 42 },     // Brown
-        RGBColor { 0: 0, 1: 255, 2: 127 },     // Sprin1 Green
-        RGBColor { 0: 70, 1: 130, 2: 180 },    // Steel Blue
-        RGBColor { 0: 255, 1: 99, 2: 71 },     // Tomato
-        RGBColor { 0: 147, 1: 112, 2: 219 },   // Medium Purple
-        RGBColor { 0: 255, 1: 215, 2: 0 },     // Gold
-        RGBColor { 0: 0, 1: 128, 2: 128 },     // Dark Cyan
-        RGBColor { 0: 255, 1: 140, 2: 0 },     // Dark Oran1e
-        RGBColor { 0: 75, 1: 0, 2: 130 },      // Indi1o
+        RGBColor(255, 0, 0),       // Red
+        RGBColor(0, 255, 0),       // Green
+        RGBColor(0, 0, 255),       // Blue
+        RGBColor(255, 255, 0),     // Yellow
+        RGBColor(255, 0, 255),     // Ma1enta
+        RGBColor(0, 255, 255),     // Cyan
+        RGBColor(128, 0, 0),       // Maroon
+        RGBColor(0, 128, 0),       // Dark Green
+        RGBColor(0, 0, 128),       // Navy
+        RGBColor(128, 128, 0),     // Olive
+        RGBColor(128, 0, 128),     // Purple
+        RGBColor(0, 128, 128),     // Teal
+        RGBColor(192, 192, 192),   // Silver
+        RGBColor(128, 128, 128),   // Gray
+        RGBColor(255, 165, 0),     // Oran1e
+        RGBColor(255, 192, 203),   // Pink
+        RGBColor(165, 42, 42),     // Brown
+        RGBColor(0, 255, 127),     // Sprin1 Green
+        RGBColor(70, 130, 180),    // Steel Blue
+        RGBColor(255, 99, 71),     // Tomato
+        RGBColor(147, 112, 219),   // Medium Purple
+        RGBColor(255, 215, 0),     // Gold
+        RGBColor(0, 128, 128),     // Dark Cyan
+        RGBColor(255, 140, 0),     // Dark Oran1e
+        RGBColor(75, 0, 130),      // Indi1o
     ]
 }
\ No newline at end of file
@@ -1,23 +1,142 @@
+use std::error::Error;
+use std::fs;
 use std::fs::File;
-use std::io::Write;
-use ordered_float::OrderedFloat;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
 
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use serde_json::from_str;
+
+use crate::genetic::evaluate::travel_time;
 use crate::structs::config::Config;
+use crate::structs::io::Info;
 use crate::structs::nurse::Individual;
 
-pub fn save_individual(population: &Vec<Individual>, config: &Config) {
-    let mut individual: Vec<Vec<i32>> = Vec::new();
-
-    let best_individual = population
-        .iter()
-        .filter(|i| i.feasible)
-        .min_by_key(|i| OrderedFloat(i.fitness))
-        .unwrap()
-        .clone();
-    for nurse in &best_individual.nurses {
-        let incremented_route = nurse.route.iter().map(|&num| num + 1).collect();
-        individual.push(incremented_route);
+/// Saved solution with enough metadata to tell where it came from.
+/// Patients are numbered from 1, like in the legacy files that only hold the routes.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SolutionFile {
+    pub run_id: String,
+    pub instance_name: String,
+    /// Seconds since the Unix epoch
+    pub timestamp: u64,
+    pub objective: f32,
+    pub feasible: bool,
+    pub seed: Option<u64>,
+    pub routes: Vec<RouteSchedule>,
+    /// Config of the run, kept as plain JSON so files saved before a config field was
+    /// added can still be read
+    #[serde(default)]
+    pub config: serde_json::Value,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RouteSchedule {
+    pub patients: Vec<i32>,
+    /// Arrival at each patient, before any waiting for the time window to open
+    pub arrival_times: Vec<f32>,
+    /// When care of each patient is finished and the nurse leaves
+    pub departure_times: Vec<f32>,
+    pub depot_return: f32,
+}
+
+impl RouteSchedule {
+    pub fn new(patients: Vec<i32>, info: &Info) -> Self {
+        let mut arrival_times = Vec::with_capacity(patients.len());
+        let mut departure_times = Vec::with_capacity(patients.len());
+        let mut time = 0.;
+        let mut prev = 0;
+        for &patient in &patients {
+            let patient_info = &info.patients[patient as usize - 1];
+            time += info.travel_times[prev][patient as usize];
+            arrival_times.push(time);
+            time = time.max(patient_info.start_time as f32) + patient_info.care_time as f32;
+            departure_times.push(time);
+            prev = patient as usize;
+        }
+        let depot_return = if patients.is_empty() { 0. } else { time + travel_time(prev, 0, info) };
+        RouteSchedule { patients, arrival_times, departure_times, depot_return }
+    }
+}
+
+impl SolutionFile {
+    pub fn new(individual: &Individual, info: &Info, config: &Config, run_id: &str) -> Self {
+        let routes = individual.nurses
+            .iter()
+            .map(|nurse| RouteSchedule::new(nurse.route.iter().map(|&patient| patient + 1).collect(), info))
+            .collect();
+        SolutionFile {
+            run_id: run_id.to_string(),
+            instance_name: info.instance_name.clone(),
+            timestamp: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
+            objective: individual.fitness,
+            feasible: individual.feasible,
+            seed: config.seed,
+            routes,
+            config: serde_json::to_value(config).unwrap_or_default(),
+        }
+    }
+
+    pub fn routes(&self) -> Vec<Vec<i32>> {
+        self.routes.iter().map(|route| route.patients.clone()).collect()
+    }
+}
+
+/// Id shared by every solution saved during one run
+pub fn new_run_id() -> String {
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    // Not drawn from the run's generator, so seeded runs still get distinct ids
+    format!("{}-{:08x}", timestamp, rand::rng().random::<u32>())
+}
+
+pub fn individual_folder(config: &Config) -> String {
+    config.output_dir.clone() + "/" + &*config.file_name + "/"
+}
+
+pub fn save_individual(best_individual: &Individual, info: &Info, config: &Config, run_id: &str) {
+    let solution = SolutionFile::new(best_individual, info, config, run_id);
+    let folder_path = individual_folder(config);
+    fs::create_dir_all(&folder_path).unwrap();
+    let file = File::create(folder_path + &*best_individual.fitness.to_string() + ".json").unwrap();
+    serde_json::to_writer_pretty(BufWriter::new(file), &solution).unwrap();
+}
+
+/// Read the routes of a saved individual, either a JSON solution file or a legacy file
+/// holding only the routes. Patients are numbered from 1 like in the saved files.
+pub fn read_individual(path: &Path) -> Result<Vec<Vec<i32>>, Box<dyn Error>> {
+    let file_content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read solution {}: {}", path.display(), e))?;
+    if let Ok(routes) = from_str::<Vec<Vec<i32>>>(&file_content) {
+        return Ok(routes);
+    }
+    let solution: SolutionFile = from_str(&file_content)
+        .map_err(|e| format!("{} is neither a solution file nor a list of routes: {}", path.display(), e))?;
+    Ok(solution.routes())
+}
+
+/// Fitness of a saved individual from its file name, `<fitness>.json` or a legacy `<fitness>`
+pub fn fitness_from_file_name(name: &str) -> Option<f32> {
+    name.strip_suffix(".json").unwrap_or(name).parse::<f32>().ok()
+}
+
+/// Find the saved individual with the lowest fitness. Files are named after their fitness.
+pub fn best_individual_file(folder_path: &str) -> Option<PathBuf> {
+    let mut min_file: f32 = f32::INFINITY;
+    let mut best_path = None;
+
+    for entry in fs::read_dir(folder_path).ok()? {
+        let entry = entry.ok()?;
+        let file_num = match entry.file_name().to_str().and_then(fitness_from_file_name) {
+            Some(file_num) => file_num,
+            None => continue,
+        };
+
+        if file_num < min_file {
+            min_file = file_num;
+            best_path = Some(entry.path());
+        }
     }
-    let mut file = File::create("individuals/".to_string() + &*config.file_name + "/" + &*best_individual.fitness.to_string()).unwrap();
-    file.write_all(format!("{:?}",&individual).as_bytes()).unwrap();
-}
\ No newline at end of file
+    best_path
+}
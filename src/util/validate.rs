@@ -0,0 +1,126 @@
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::genetic::evaluate::travel_time;
+use crate::structs::io::Info;
+
+/// A broken constraint in a solution. Patients are numbered from 1 like in saved solutions,
+/// nurses from 0 like in the printed schedule.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub enum Violation {
+    UnknownPatient { nurse: usize, patient: i32 },
+    MissingPatient { patient: usize },
+    DuplicatedPatient { patient: usize, visits: usize },
+    /// Care finished after the end of the patient's time window
+    LateCareFinish { nurse: usize, patient: usize, care_finished: f32, end_time: u32, lateness: f32 },
+    CapacityExceeded { nurse: usize, demand: u32, capacity: u32 },
+    LateDepotReturn { nurse: usize, arrival: f32, return_time: u32, lateness: f32 },
+    TooManyNurses { used: usize, available: u32 },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::UnknownPatient { nurse, patient } => write!(f, "Nurse {} visits unknown patient {}", nurse, patient),
+            Violation::MissingPatient { patient } => write!(f, "Patient {} is never visited", patient),
+            Violation::DuplicatedPatient { patient, visits } => write!(f, "Patient {} is visited {} times", patient, visits),
+            Violation::LateCareFinish { nurse, patient, care_finished, end_time, lateness } => write!(
+                f, "Nurse {} finishes patient {} at {:.2}, after {} ({:.2} late)", nurse, patient, care_finished, end_time, lateness
+            ),
+            Violation::CapacityExceeded { nurse, demand, capacity } => write!(
+                f, "Nurse {} carries a demand of {}, capacity is {} ({} over)", nurse, demand, capacity, demand - capacity
+            ),
+            Violation::LateDepotReturn { nurse, arrival, return_time, lateness } => write!(
+                f, "Nurse {} returns to the depot at {:.2}, after {} ({:.2} late)", nurse, arrival, return_time, lateness
+            ),
+            Violation::TooManyNurses { used, available } => write!(f, "Solution uses {} nurses, only {} are available", used, available),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct ValidationReport {
+    pub violations: Vec<Violation>,
+    pub total_travel_time: f32,
+    pub nurses_used: usize,
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+/// Check a solution given as one-based routes, independent of the fitness function,
+/// and report every violated constraint.
+pub fn validate_solution(routes: &[Vec<i32>], info: &Info) -> ValidationReport {
+    let mut report = ValidationReport::default();
+    let nbr_patients = info.patients.len();
+
+    let mut visits = vec![0; nbr_patients + 1];
+    for (nurse, route) in routes.iter().enumerate() {
+        if route.is_empty() {
+            continue;
+        }
+        report.nurses_used += 1;
+
+        let mut time = 0.;
+        let mut demand = 0;
+        let mut prev = 0;
+        for &patient in route {
+            if patient < 1 || patient as usize > nbr_patients {
+                report.violations.push(Violation::UnknownPatient { nurse, patient });
+                continue;
+            }
+            let patient = patient as usize;
+            visits[patient] += 1;
+            let patient_info = &info.patients[patient - 1];
+
+            let travel = info.travel_times[prev][patient];
+            report.total_travel_time += travel;
+            time = (time + travel).max(patient_info.start_time as f32) + patient_info.care_time as f32;
+            if time > patient_info.end_time as f32 {
+                report.violations.push(Violation::LateCareFinish {
+                    nurse,
+                    patient,
+                    care_finished: time,
+                    end_time: patient_info.end_time,
+                    lateness: time - patient_info.end_time as f32,
+                });
+            }
+            demand += patient_info.demand;
+            prev = patient;
+        }
+
+        // Read the way back like the solver does, so both agree on asymmetric matrices
+        let travel = travel_time(prev, 0, info);
+        report.total_travel_time += travel;
+        time += travel;
+        if demand > info.capacity_nurse {
+            report.violations.push(Violation::CapacityExceeded { nurse, demand, capacity: info.capacity_nurse });
+        }
+        if time > info.depot.return_time as f32 {
+            report.violations.push(Violation::LateDepotReturn {
+                nurse,
+                arrival: time,
+                return_time: info.depot.return_time,
+                lateness: time - info.depot.return_time as f32,
+            });
+        }
+    }
+
+    // Empty routes do not need a nurse
+    if report.nurses_used > info.nbr_nurses as usize {
+        report.violations.push(Violation::TooManyNurses { used: report.nurses_used, available: info.nbr_nurses });
+    }
+
+    for (patient, &count) in visits.iter().enumerate().skip(1) {
+        match count {
+            0 => report.violations.push(Violation::MissingPatient { patient }),
+            1 => {}
+            visits => report.violations.push(Violation::DuplicatedPatient { patient, visits }),
+        }
+    }
+    report
+}